@@ -0,0 +1,136 @@
+//!
+//! Derive macros for [`http-whatever`](https://docs.rs/http-whatever), re-exported
+//! by that crate behind its `derive` feature.
+//!
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitInt, LitStr};
+
+///
+/// Derive `http_whatever::Domain`, and `Display`, for an enum of unit variants
+/// naming the domains of an application.
+///
+/// The name of the domain of a variant is the name of the variant, unless
+/// given with `#[http_domain(name = "...")]`. A default status for the errors
+/// of the domain can be given with `#[http_domain(status = 404)]`.
+///
+#[proc_macro_derive(HttpDomain, attributes(http_domain))]
+pub fn derive_http_domain(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+///
+/// The domain of one variant.
+///
+struct Variant {
+    ident: syn::Ident,
+    name: String,
+    status: Option<u16>,
+}
+
+fn parse_variant(variant: &syn::Variant) -> syn::Result<Variant> {
+    if !matches!(variant.fields, Fields::Unit) {
+        return Err(Error::new_spanned(
+            variant,
+            "HttpDomain variants cannot have fields",
+        ));
+    }
+    let mut parsed = Variant {
+        ident: variant.ident.clone(),
+        name: variant.ident.to_string(),
+        status: None,
+    };
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("http_domain") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let name: LitStr = meta.value()?.parse()?;
+                if name.value().contains(':') {
+                    return Err(Error::new_spanned(name, "domain names cannot contain ':'"));
+                }
+                parsed.name = name.value();
+                Ok(())
+            } else if meta.path.is_ident("status") {
+                let status: LitInt = meta.value()?.parse()?;
+                let value: u16 = status.base10_parse()?;
+                if !(100..=999).contains(&value) {
+                    return Err(Error::new_spanned(
+                        status,
+                        "HTTP statuses must be between 100 and 999",
+                    ));
+                }
+                parsed.status = Some(value);
+                Ok(())
+            } else {
+                Err(meta.error("expected `name` or `status`"))
+            }
+        })?;
+    }
+    Ok(parsed)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "HttpDomain can only be derived for enums",
+        ));
+    };
+    let variants = data
+        .variants
+        .iter()
+        .map(parse_variant)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let names = variants.iter().map(|variant| {
+        let (variant, name) = (&variant.ident, &variant.name);
+        quote!(Self::#variant => #name)
+    });
+    let statuses = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        match variant.status {
+            Some(status) => quote! {
+                Self::#ident => ::http_whatever::__private::StatusCode::from_u16(#status).ok()
+            },
+            None => quote!(Self::#ident => ::core::option::Option::None),
+        }
+    });
+    let as_domain = if variants.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote!(match self { #(#names,)* })
+    };
+    let default_status = if variants.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote!(match self { #(#statuses,)* })
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::http_whatever::Domain for #ident #ty_generics #where_clause {
+            fn as_domain(&self) -> &str {
+                #as_domain
+            }
+
+            fn default_status(
+                &self,
+            ) -> ::core::option::Option<::http_whatever::__private::StatusCode> {
+                #default_status
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(::http_whatever::Domain::as_domain(self))
+            }
+        }
+    })
+}
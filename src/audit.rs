@@ -0,0 +1,221 @@
+//!
+//! Audit records of errors for security information pipelines.
+//!
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use http::StatusCode;
+
+use crate::date::format_rfc3339;
+use crate::json::JsonObject;
+use crate::{config, ErrorRecord, HttpWhatever};
+
+///
+/// The version of the [`AuditRecord`] schema, incremented whenever it changes
+/// incompatibly.
+///
+pub const AUDIT_RECORD_VERSION: u32 = 1;
+
+///
+/// A stable, versioned record of an error for security information (SIEM)
+/// pipelines, created with [`HttpWhatever::as_audit_record`].
+///
+/// Records can be emitted automatically, whenever a response is rendered for
+/// an error in one of a selected set of domains, with [`config::audit`].
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AuditRecord {
+    ///
+    /// The [`AUDIT_RECORD_VERSION`] of the record.
+    ///
+    pub version: u32,
+    ///
+    /// The identifier of the error.
+    ///
+    pub id: String,
+    ///
+    /// Who caused the error - the `user_id` metadata of the error, if recorded.
+    ///
+    pub who: Option<String>,
+    ///
+    /// What happened - the (redacted) message of the error.
+    ///
+    pub what: String,
+    ///
+    /// When the error was created.
+    ///
+    pub when: SystemTime,
+    ///
+    /// The HTTP status of the error.
+    ///
+    pub status: StatusCode,
+    ///
+    /// The domain of the error.
+    ///
+    pub domain: String,
+    ///
+    /// The fingerprint of the error, as in its [`ErrorRecord`].
+    ///
+    pub fingerprint: u64,
+    ///
+    /// The request context of the error - its metadata, with redacted values.
+    ///
+    pub context: BTreeMap<String, String>,
+}
+
+impl AuditRecord {
+    ///
+    /// Return the record as a JSON object, with the time as an RFC 3339 timestamp
+    /// and the fingerprint as 16 hexadecimal digits.
+    ///
+    pub fn to_json(&self) -> String {
+        let mut context = JsonObject::new();
+        for (key, value) in &self.context {
+            context.string(key, value);
+        }
+        let mut record = JsonObject::new();
+        record
+            .number("version", self.version)
+            .string("id", &self.id);
+        match &self.who {
+            Some(who) => record.string("who", who),
+            None => record.raw("who", "null"),
+        };
+        record
+            .string("what", &self.what)
+            .string("when", &format_rfc3339(self.when))
+            .number("status", self.status.as_u16())
+            .string("domain", &self.domain)
+            .string("fingerprint", &format!("{:016x}", self.fingerprint))
+            .raw("context", &context.finish())
+            .finish()
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// Return the [`AuditRecord`] of this error.
+    ///
+    pub fn as_audit_record(&self) -> AuditRecord {
+        let record = ErrorRecord::of(self);
+        AuditRecord {
+            version: AUDIT_RECORD_VERSION,
+            id: self.id().to_string(),
+            who: self.metadata().get("user_id").cloned(),
+            what: self.rendered_message().into_owned(),
+            when: self.context().created(),
+            status: record.status,
+            domain: record.domain,
+            fingerprint: record.fingerprint,
+            context: self
+                .metadata()
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.clone(),
+                        crate::render_text(self.id(), value).into_owned(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    ///
+    /// Pass the audit record of this error to the configured audit observer, if
+    /// its domain is audited.
+    ///
+    pub(crate) fn emit_audit_record(&self) {
+        let config = config::current();
+        if let Some(observer) = &config.audit_observer {
+            let domain = self.parts().1;
+            if config.audit_domains.iter().any(|audited| audited == domain) {
+                observer(&self.as_audit_record());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+pub(crate) mod testing {
+    use std::sync::Mutex;
+
+    use crate::config;
+
+    static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    ///
+    /// Audit the "Audited" domain, collecting the ids of the records.
+    ///
+    pub(crate) fn audit() {
+        config::audit(["Audited"], |record| {
+            RECORDS.lock().unwrap().push(record.id.clone());
+        });
+    }
+
+    ///
+    /// The number of records emitted for the error with the given id.
+    ///
+    pub(crate) fn records_of(id: &str) -> usize {
+        RECORDS.lock().unwrap().iter().filter(|r| *r == id).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::testing;
+    use crate::{config, HttpError, HttpWhatever};
+    use http::{Response, StatusCode};
+
+    #[test]
+    fn audit_record() {
+        let record = HttpWhatever::new("403:Auth:Token revoked")
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+            .with_metadata("user_id", "alice")
+            .as_audit_record();
+
+        assert_eq!(record.status, StatusCode::FORBIDDEN);
+        assert_eq!(record.who.as_deref(), Some("alice"));
+
+        let json = record.to_json();
+        assert!(json.starts_with(
+            r#"{"version":1,"id":"01ARZ3NDEKTSV4RRFFQ69G5FAV","who":"alice","what":"Token revoked","when":""#
+        ));
+        assert!(json.ends_with(&format!(
+            r#"","status":403,"domain":"Auth","fingerprint":"{:016x}","context":{{"user_id":"alice"}}}}"#,
+            record.fingerprint
+        )));
+    }
+
+    #[test]
+    fn redacted_context() {
+        // A redactor only touching this test's marker, as the setting is global.
+        config::configure(|config| {
+            config.redactor = Some(Arc::new(|text: &str| {
+                text.replace("audit-secret", "[REDACTED]")
+            }))
+        });
+        let record = HttpWhatever::new("403:Auth:Token revoked")
+            .with_metadata("token", "audit-secret")
+            .as_audit_record();
+
+        assert_eq!(record.context["token"], "[REDACTED]");
+    }
+
+    #[test]
+    fn one_record_per_error() {
+        testing::audit();
+        let error = HttpWhatever::new("403:Audited:Token revoked");
+        let id = error.id().to_string();
+
+        let _ = error.as_http_response_builder();
+        assert_eq!(testing::records_of(&id), 0);
+
+        let _: Response<String> = error.as_http_json_response();
+        let _: Response<String> = (&error as &dyn HttpError).as_http_string_response();
+        assert_eq!(testing::records_of(&id), 1);
+    }
+}
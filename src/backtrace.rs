@@ -0,0 +1,146 @@
+//!
+//! Rendering of captured backtraces, capped and filtered according to the
+//! current [`config`](crate::config).
+//!
+use std::fmt::Write;
+
+use snafu::Backtrace;
+
+use crate::{config, BacktraceStyle, HttpWhatever};
+
+///
+/// Path prefixes of the frames removed by
+/// [`Config::filter_backtrace`](crate::config::Config::filter_backtrace): this
+/// crate, snafu, the async runtimes and the backtrace capture itself.
+///
+const FILTERED_PREFIXES: [&str; 7] = [
+    "http_whatever::",
+    "snafu::",
+    "tokio::",
+    "async_std::",
+    "futures_util::",
+    "std::backtrace::",
+    "std::backtrace_rs::",
+];
+
+fn is_filtered(symbol: &str) -> bool {
+    let symbol = symbol.trim_start_matches('<');
+    FILTERED_PREFIXES
+        .iter()
+        .any(|prefix| symbol.starts_with(prefix))
+}
+
+///
+/// Cap and filter the frames of the `Display` output of a backtrace. Output that
+/// holds no frames - a disabled backtrace, for example - is returned unchanged.
+///
+fn render(text: &str, max_frames: Option<usize>, filter: bool) -> String {
+    let mut frames: Vec<(&str, Vec<&str>)> = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        match trimmed.split_once(": ") {
+            Some((index, symbol)) if index.bytes().all(|b| b.is_ascii_digit()) => {
+                frames.push((symbol, Vec::new()));
+            }
+            _ => match frames.last_mut() {
+                Some((_, locations)) => locations.push(trimmed),
+                None => return text.to_owned(),
+            },
+        }
+    }
+    if frames.is_empty() {
+        return text.to_owned();
+    }
+
+    let kept: Vec<_> = frames
+        .iter()
+        .filter(|(symbol, _)| !(filter && is_filtered(symbol)))
+        .collect();
+    let shown = max_frames.unwrap_or(usize::MAX).min(kept.len());
+    let mut out = String::new();
+    for (index, (symbol, locations)) in kept.iter().take(shown).enumerate() {
+        let _ = writeln!(out, "{index:>4}: {symbol}");
+        for location in locations {
+            let _ = writeln!(out, "             {location}");
+        }
+    }
+    if shown < kept.len() {
+        let _ = writeln!(out, "      ... {} more frames", kept.len() - shown);
+    }
+    out
+}
+
+impl HttpWhatever {
+    ///
+    /// The backtrace captured when this error was created.
+    ///
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    ///
+    /// Return the backtrace captured when this error was created, rendered in the
+    /// given style - `None` for [`BacktraceStyle::Off`].
+    ///
+    pub fn rendered_backtrace_with(&self, style: BacktraceStyle) -> Option<String> {
+        match style {
+            BacktraceStyle::Off => None,
+            BacktraceStyle::Short => Some(self.rendered_backtrace()),
+            BacktraceStyle::Full => Some(self.backtrace.to_string()),
+        }
+    }
+
+    ///
+    /// Return the backtrace captured when this error was created, with at most
+    /// [`Config::max_backtrace_frames`](crate::config::Config::max_backtrace_frames)
+    /// frames and, if [`Config::filter_backtrace`](crate::config::Config::filter_backtrace)
+    /// is set, without the frames of this crate, snafu and the async runtime.
+    ///
+    pub fn rendered_backtrace(&self) -> String {
+        let config = config::current();
+        render(
+            &self.backtrace.to_string(),
+            config.max_backtrace_frames,
+            config.filter_backtrace,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    const BACKTRACE: &str = "   0: std::backtrace::Backtrace::force_capture
+             at /rustc/library/std/src/backtrace.rs:312:9
+   1: <http_whatever::context::ErrorContext as snafu::GenerateImplicitData>::generate
+   2: app::handler
+             at ./src/main.rs:10:5
+   3: tokio::runtime::task::raw::poll
+   4: app::main
+             at ./src/main.rs:20:5
+";
+
+    #[test]
+    fn render_backtrace() {
+        assert_eq!(render(BACKTRACE, None, false).lines().count(), 8);
+        assert_eq!(
+            render(BACKTRACE, None, true),
+            "   0: app::handler
+             at ./src/main.rs:10:5
+   1: app::main
+             at ./src/main.rs:20:5
+"
+        );
+        assert_eq!(
+            render(BACKTRACE, Some(1), true),
+            "   0: app::handler
+             at ./src/main.rs:10:5
+      ... 1 more frames
+"
+        );
+        assert_eq!(
+            render("disabled backtrace", Some(1), true),
+            "disabled backtrace"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//!
+//! Per-item outcomes for bulk endpoints.
+//!
+use core::fmt::Display;
+
+use http::{header::CONTENT_TYPE, Response, StatusCode};
+
+use crate::json::{self, JsonObject};
+use crate::{HttpResult, HttpWhatever};
+
+///
+/// The outcome of a bulk operation (bulk-create, bulk-update...), pairing each
+/// item identifier with either success or the [`HttpWhatever`] that item failed with.
+///
+/// The overall status is `200 OK` when every item succeeded, and
+/// `207 Multi-Status` as soon as one of them failed.
+///
+/// ```
+/// use http_whatever::prelude::*;
+/// use http_whatever::BatchOutcome;
+///
+/// let mut outcome = BatchOutcome::new();
+/// outcome.succeeded("a");
+/// outcome.failed("b", HttpWhatever::new(http_err!(404, "Catalog", "No such item")));
+///
+/// assert_eq!(outcome.status(), http::StatusCode::MULTI_STATUS);
+/// ```
+///
+#[derive(Debug)]
+pub struct BatchOutcome<K> {
+    items: Vec<(K, Option<HttpWhatever>)>,
+}
+
+impl<K> Default for BatchOutcome<K> {
+    fn default() -> Self {
+        Self { items: Vec::new() }
+    }
+}
+
+impl<K> FromIterator<(K, Option<HttpWhatever>)> for BatchOutcome<K> {
+    fn from_iter<I: IntoIterator<Item = (K, Option<HttpWhatever>)>>(iter: I) -> Self {
+        Self {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<K> BatchOutcome<K>
+where
+    K: Display,
+{
+    ///
+    /// Create an empty outcome.
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Record the item identified by `id` as having succeeded.
+    ///
+    pub fn succeeded(&mut self, id: K) {
+        self.items.push((id, None));
+    }
+
+    ///
+    /// Record the item identified by `id` as having failed with `error`.
+    ///
+    pub fn failed(&mut self, id: K, error: HttpWhatever) {
+        self.items.push((id, Some(error)));
+    }
+
+    ///
+    /// Record the result of processing the item identified by `id`, returning
+    /// the success value, if any.
+    ///
+    pub fn record<T>(&mut self, id: K, result: HttpResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.succeeded(id);
+                Some(value)
+            }
+            Err(error) => {
+                self.failed(id, error);
+                None
+            }
+        }
+    }
+
+    ///
+    /// Iterate over the items that failed, with their errors.
+    ///
+    pub fn failures(&self) -> impl Iterator<Item = (&K, &HttpWhatever)> {
+        self.items
+            .iter()
+            .filter_map(|(id, error)| error.as_ref().map(|error| (id, error)))
+    }
+
+    ///
+    /// Return `true` if no item failed.
+    ///
+    pub fn is_success(&self) -> bool {
+        self.items.iter().all(|(_, error)| error.is_none())
+    }
+
+    ///
+    /// The overall status of the batch - `200 OK` or `207 Multi-Status`.
+    ///
+    pub fn status(&self) -> StatusCode {
+        if self.is_success() {
+            StatusCode::OK
+        } else {
+            StatusCode::MULTI_STATUS
+        }
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the outcome, with
+    /// a JSON body generated from the `into` method.
+    ///
+    /// The string in the response body will be of the format
+    ///
+    /// `{"succeeded":["<id>",...],"failed":[{"id":"<id>","status":<status>,"message":"<message>","domain":"<domain>","error_id":"<error id>"},...]}`
+    ///
+    /// where the `error_id` of a failed item is the [`ErrorId`](crate::ErrorId) of
+    /// its error, and its message is redacted as for error responses.
+    ///
+    /// The `content-type` header of the response will be `application/json`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the status is always valid that isn't actually
+    /// possible.
+    pub fn as_http_json_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        let succeeded = self
+            .items
+            .iter()
+            .filter(|(_, error)| error.is_none())
+            .map(|(id, _)| json::string(&id.to_string()));
+        let failed = self.failures().map(|(id, error)| {
+            let parts = error.parts();
+            JsonObject::new()
+                .string("id", &id.to_string())
+                .number("status", parts.2.as_u16())
+                .string("message", &error.rendered_message())
+                .string("domain", parts.1)
+                .string("error_id", error.id().as_str())
+                .finish()
+        });
+        let body_str = JsonObject::new()
+            .raw("succeeded", &json::array(succeeded))
+            .raw("failed", &json::array(failed))
+            .finish();
+        Response::builder()
+            .status(self.status())
+            .header(CONTENT_TYPE, "application/json")
+            .body(body_str.into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BatchOutcome;
+    use crate::prelude::*;
+    use http::{Response, StatusCode};
+
+    #[test]
+    fn all_succeeded() {
+        let mut outcome = BatchOutcome::new();
+        outcome.succeeded(1);
+        outcome.succeeded(2);
+        let response: Response<String> = outcome.as_http_json_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), "{\"succeeded\":[\"1\",\"2\"],\"failed\":[]}");
+    }
+
+    #[test]
+    fn partial_failure() {
+        let mut outcome = BatchOutcome::new();
+        outcome.succeeded("a");
+        let value: Option<()> = outcome.record(
+            "b",
+            Err(HttpWhatever::new(http_err!(409, "Catalog", "Already \"there\"")).with_id("E1")),
+        );
+        let response: Response<String> = outcome.as_http_json_response();
+
+        assert!(value.is_none());
+        assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+        assert_eq!(
+            response.body(),
+            "{\"succeeded\":[\"a\"],\"failed\":[{\"id\":\"b\",\"status\":409,\"message\":\"Already \\\"there\\\"\",\"domain\":\"Catalog\",\"error_id\":\"E1\"}]}"
+        );
+    }
+}
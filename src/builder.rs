@@ -0,0 +1,167 @@
+//!
+//! A fluent builder of [`HttpWhatever`]s.
+//!
+use std::error::Error;
+
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+use crate::{Domain, HttpWhatever};
+
+///
+/// Builds an [`HttpWhatever`] from its parts, created by
+/// [`HttpWhatever::builder`].
+///
+/// Unless set otherwise, the status is the [`Domain::default_status`] of the
+/// domain or else `500 Internal Server Error`, the domain is "Internal" and the
+/// message is the canonical reason of the status.
+///
+/// ```rust
+/// use http::header::WWW_AUTHENTICATE;
+/// use http::{HeaderValue, StatusCode};
+/// use http_whatever::HttpWhatever;
+///
+/// let user = "alice";
+/// let error = HttpWhatever::builder()
+///     .status(StatusCode::UNAUTHORIZED)
+///     .domain("Auth")
+///     .message(format_args!("No session for {user}"))
+///     .header(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))
+///     .build();
+///
+/// assert!(error.is(StatusCode::UNAUTHORIZED, "Auth"));
+/// assert_eq!(error.parts().0, "No session for alice");
+/// ```
+///
+#[derive(Debug)]
+#[must_use = "call `build` to create the HttpWhatever"]
+pub struct HttpWhateverBuilder {
+    status: Option<StatusCode>,
+    default_status: Option<StatusCode>,
+    domain: String,
+    message: Option<String>,
+    source: Option<Box<dyn Error + Send + Sync>>,
+    headers: HeaderMap,
+}
+
+impl HttpWhateverBuilder {
+    ///
+    /// Set the status of the error.
+    ///
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    ///
+    /// Set the domain of the error.
+    ///
+    pub fn domain(mut self, domain: impl Domain) -> Self {
+        self.domain = domain.as_domain().to_owned();
+        self.default_status = domain.default_status();
+        self
+    }
+
+    ///
+    /// Set the message of the error.
+    ///
+    pub fn message(mut self, message: impl std::fmt::Display) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    ///
+    /// Set the source of the error.
+    ///
+    pub fn source(mut self, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    ///
+    /// Add a header to the HTTP responses generated from the error.
+    ///
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    ///
+    /// Create the [`HttpWhatever`].
+    ///
+    pub fn build(self) -> HttpWhatever {
+        let status = self
+            .status
+            .or(self.default_status)
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let message = self.message.unwrap_or_else(|| {
+            status
+                .canonical_reason()
+                .unwrap_or("Unknown error")
+                .to_owned()
+        });
+        let mut error = HttpWhatever::categorized(status, &self.domain, message, self.source);
+        for (name, value) in &self.headers {
+            error = error.with_header(name.clone(), value.clone());
+        }
+        error
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// Start building an `HttpWhatever` - see [`HttpWhateverBuilder`].
+    ///
+    pub fn builder() -> HttpWhateverBuilder {
+        HttpWhateverBuilder {
+            status: None,
+            default_status: None,
+            domain: "Internal".to_owned(),
+            message: None,
+            source: None,
+            headers: HeaderMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpWhatever;
+    use http::header::RETRY_AFTER;
+    use http::{HeaderValue, StatusCode};
+    use std::num::ParseIntError;
+
+    #[test]
+    fn builder() {
+        let error = HttpWhatever::builder().build();
+        assert_eq!(
+            error.parts(),
+            (
+                "Internal Server Error",
+                "Internal",
+                StatusCode::INTERNAL_SERVER_ERROR
+            )
+        );
+
+        let error = HttpWhatever::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .domain("Billing")
+            .message("Ledger unavailable")
+            .source("x".parse::<u8>().unwrap_err())
+            .header(RETRY_AFTER, HeaderValue::from_static("30"))
+            .build();
+        assert!(error.is(StatusCode::SERVICE_UNAVAILABLE, "Billing"));
+        assert_eq!(error.parts().0, "Ledger unavailable");
+        assert!(error.chain_contains::<ParseIntError>());
+        assert_eq!(error.response_headers().get(RETRY_AFTER).unwrap(), "30");
+    }
+
+    #[test]
+    fn domain_with_colon() {
+        let error = HttpWhatever::builder()
+            .status(StatusCode::NOT_FOUND)
+            .domain("api:v2")
+            .message("gone")
+            .build();
+        assert_eq!(error.parts(), ("gone", "api_v2", StatusCode::NOT_FOUND));
+    }
+}
@@ -0,0 +1,123 @@
+//!
+//! CBOR (RFC 8949) rendering, for constrained and IoT clients.
+//!
+use http::header::CONTENT_TYPE;
+use http::Response;
+
+use crate::{HttpError, HttpWhatever};
+
+const UNSIGNED: u8 = 0;
+const TEXT: u8 = 3;
+const MAP: u8 = 5;
+
+///
+/// Write the head of a data item of the given major type and argument.
+///
+fn write_head(out: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend([major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(argument.to_be_bytes());
+        }
+    }
+}
+
+fn write_text(out: &mut Vec<u8>, text: &str) {
+    write_head(out, TEXT, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+impl HttpWhatever {
+    fn cbor_body(&self) -> Vec<u8> {
+        let parts = self.parts();
+        let mut body = Vec::new();
+        write_head(&mut body, MAP, 3);
+        write_text(&mut body, "message");
+        write_text(&mut body, &self.rendered_message());
+        write_text(&mut body, "domain");
+        write_text(&mut body, parts.1);
+        write_text(&mut body, "status");
+        write_head(&mut body, UNSIGNED, u64::from(parts.2.as_u16()));
+        body
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with a
+    /// CBOR body generated from the `into` method.
+    ///
+    /// The body is a map of the form
+    ///
+    /// `{"message": "<message>", "domain": "<domain>", "status": <status>}`
+    ///
+    /// The `content-type` header of the response will be `application/cbor`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(Vec<u8>)`, that isn't actually
+    /// possible.
+    pub fn as_http_cbor_response<B>(&self) -> Response<B>
+    where
+        B: From<Vec<u8>>,
+    {
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "application/cbor")
+            .body(self.cbor_body().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_head, UNSIGNED};
+    use crate::HttpWhatever;
+    use http::header::CONTENT_TYPE;
+    use http::Response;
+
+    #[test]
+    fn heads() {
+        let head = |argument| {
+            let mut out = Vec::new();
+            write_head(&mut out, UNSIGNED, argument);
+            out
+        };
+        assert_eq!(head(23), [0x17]);
+        assert_eq!(head(24), [0x18, 0x18]);
+        assert_eq!(head(500), [0x19, 0x01, 0xf4]);
+        assert_eq!(head(1_000_000), [0x1a, 0x00, 0x0f, 0x42, 0x40]);
+    }
+
+    #[test]
+    fn cbor_body() {
+        let response: Response<Vec<u8>> = HttpWhatever::new("404:Api:Gone").as_http_cbor_response();
+
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/cbor"
+        );
+        let mut expected = vec![0xa3, 0x67];
+        expected.extend(b"message");
+        expected.push(0x64);
+        expected.extend(b"Gone");
+        expected.push(0x66);
+        expected.extend(b"domain");
+        expected.push(0x63);
+        expected.extend(b"Api");
+        expected.push(0x66);
+        expected.extend(b"status");
+        expected.extend([0x19, 0x01, 0x94]);
+        assert_eq!(response.body(), &expected);
+    }
+}
@@ -0,0 +1,352 @@
+//!
+//! Process-wide settings that influence how errors are rendered.
+//!
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::{AuditRecord, ErrorContext, Redactor, SecretScan};
+
+///
+/// How the status of an error whose message carries no explicit status, or an
+/// explicit `500`, is determined, when the error wraps other `HttpWhatever`s.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusPolicy {
+    ///
+    /// The outermost error's status wins, so an error without an explicit status
+    /// is always a `500` (the default).
+    ///
+    #[default]
+    Outermost,
+    ///
+    /// The explicit status of the innermost wrapped `HttpWhatever` that has one
+    /// is used, so that re-wrapping an error for context does not erase a
+    /// carefully chosen `404`. An explicit `500`, as written by the two argument
+    /// form of [`http_err!`](crate::http_err), counts as no status.
+    ///
+    InnermostExplicit,
+}
+
+///
+/// Whether responses are rendered for production or for debugging.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    ///
+    /// Responses carry only the message, domain and identifiers (the default).
+    ///
+    #[default]
+    Production,
+    ///
+    /// JSON responses also carry the [`HttpWhatever::details`](crate::HttpWhatever::details)
+    /// of the error - its source chain and runtime context. Never use this where
+    /// responses reach untrusted clients.
+    ///
+    Debug,
+}
+
+///
+/// How much of the captured backtrace [`HttpWhatever::report`](crate::HttpWhatever::report)
+/// and debug responses include.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BacktraceStyle {
+    ///
+    /// No backtrace (the default).
+    ///
+    #[default]
+    Off,
+    ///
+    /// The backtrace capped and filtered as set by [`Config::max_backtrace_frames`]
+    /// and [`Config::filter_backtrace`] - the application frames only, if the
+    /// latter is set.
+    ///
+    Short,
+    ///
+    /// Every frame of the backtrace.
+    ///
+    Full,
+}
+
+///
+/// A schema for the JSON bodies of error responses, so that the same errors can
+/// serve internal APIs, which want everything, and public APIs, which want little.
+///
+/// Without a preset the JSON body is that documented on
+/// [`HttpWhatever::as_http_json_response`](crate::HttpWhatever::as_http_json_response).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonPreset {
+    ///
+    /// `{"message":"<message>"}`
+    ///
+    Minimal,
+    ///
+    /// `{"message":"<message>","domain":"<domain>","status":<status>,"code":"<code>","id":"<id>"}`,
+    /// where the code is the canonical reason of the status, e.g. `NOT_FOUND`,
+    /// plus the locale, field errors and data of the error, if any.
+    ///
+    Standard,
+    ///
+    /// The [`JsonPreset::Standard`] members plus the `chain` of source messages,
+    /// and the metadata exposed to clients - see [`Config::exposed_metadata`] - as
+    /// `extensions`, with its `trace_id` and `help`, if any, also as members.
+    ///
+    Verbose,
+}
+
+///
+/// The body format of [`HttpWhatever::as_http_default_response`](crate::HttpWhatever::as_http_default_response).
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    ///
+    /// No body, as [`HttpWhatever::as_http_response`](crate::HttpWhatever::as_http_response).
+    ///
+    Empty,
+    ///
+    /// A plain text body, as [`HttpWhatever::as_http_string_response`](crate::HttpWhatever::as_http_string_response).
+    ///
+    Text,
+    ///
+    /// A JSON body, as [`HttpWhatever::as_http_json_response`](crate::HttpWhatever::as_http_json_response)
+    /// (the default).
+    ///
+    #[default]
+    Json,
+}
+
+///
+/// An observer of [`AuditRecord`]s - see [`audit`].
+///
+pub type AuditObserver = Arc<dyn Fn(&AuditRecord) + Send + Sync>;
+
+///
+/// The process-wide settings of this crate.
+///
+/// Settings are changed with [`configure`], typically once at application startup.
+///
+/// The initial settings are the defaults, overridden by these environment
+/// variables, read when the settings are first used:
+///
+/// * `HTTP_WHATEVER_MODE`: `debug` or `prod` - see [`Mode`]
+/// * `HTTP_WHATEVER_FORMAT`: `empty`, `text` or `json` - see [`ResponseFormat`]
+/// * `HTTP_WHATEVER_BACKTRACE`: `off`, `full`, or `short` for the application
+///   frames only - see [`BacktraceStyle`]
+/// * `HTTP_WHATEVER_BACKTRACE_FRAMES`: the maximum number of backtrace frames shown
+///
+/// Unrecognized values are ignored.
+///
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub struct Config {
+    ///
+    /// The [`Redactor`] applied to messages and source strings before they reach
+    /// response bodies and [`HttpWhatever::details`](crate::HttpWhatever::details).
+    /// Default: none.
+    ///
+    pub redactor: Option<Arc<dyn Redactor>>,
+    ///
+    /// Whether, in builds with debug assertions, error text is scanned for likely
+    /// secrets when it is rendered. Default: [`SecretScan::Off`].
+    ///
+    pub secret_scan: SecretScan,
+    ///
+    /// How the status of errors without an explicit status is determined.
+    /// Default: [`StatusPolicy::Outermost`].
+    ///
+    pub status_policy: StatusPolicy,
+    ///
+    /// Whether the [`RuntimeContext`](crate::RuntimeContext) - thread, task, host
+    /// and pod - is captured when errors are created. Default: `false`.
+    ///
+    pub capture_runtime_context: bool,
+    ///
+    /// Enrichers invoked, in order, on the [`ErrorContext`] of every error when it
+    /// is created - to record the build version, region or tenant, for example.
+    /// Default: none.
+    ///
+    pub enrichers: Vec<fn(&mut ErrorContext)>,
+    ///
+    /// The maximum number of frames shown by
+    /// [`HttpWhatever::rendered_backtrace`](crate::HttpWhatever::rendered_backtrace).
+    /// Default: none, all frames are shown.
+    ///
+    pub max_backtrace_frames: Option<usize>,
+    ///
+    /// Whether [`HttpWhatever::rendered_backtrace`](crate::HttpWhatever::rendered_backtrace)
+    /// leaves out the frames of this crate, snafu and the async runtime, so that
+    /// it focuses on application frames. Default: `false`.
+    ///
+    pub filter_backtrace: bool,
+    ///
+    /// Whether responses are rendered for production or debugging.
+    /// Default: [`Mode::Production`].
+    ///
+    pub mode: Mode,
+    ///
+    /// The body format of [`HttpWhatever::as_http_default_response`](crate::HttpWhatever::as_http_default_response).
+    /// Default: [`ResponseFormat::Json`].
+    ///
+    pub format: ResponseFormat,
+    ///
+    /// How much of the backtrace [`HttpWhatever::report`](crate::HttpWhatever::report)
+    /// and debug responses include. Default: [`BacktraceStyle::Off`].
+    ///
+    pub backtrace_style: BacktraceStyle,
+    ///
+    /// The schema of the JSON bodies of error responses. Default: none, the
+    /// schema documented on
+    /// [`HttpWhatever::as_http_json_response`](crate::HttpWhatever::as_http_json_response).
+    ///
+    pub json_preset: Option<JsonPreset>,
+    ///
+    /// Whether the JSON body documented on
+    /// [`HttpWhatever::as_http_json_response`](crate::HttpWhatever::as_http_json_response)
+    /// includes the numeric HTTP status as a `status` member. Default: `false`.
+    ///
+    pub json_status: bool,
+    ///
+    /// The metadata keys whose values, redacted, may be sent to clients - in the
+    /// `extensions` of [`JsonPreset::Verbose`] bodies and the `ErrorInfo` of gRPC
+    /// status details. In [`Mode::Debug`] all metadata is sent. Default: none.
+    ///
+    pub exposed_metadata: Vec<String>,
+    ///
+    /// The domains of the errors whose [`AuditRecord`]s are passed to the
+    /// [`Config::audit_observer`] when responses are rendered for them.
+    /// Default: none.
+    ///
+    pub audit_domains: Vec<String>,
+    ///
+    /// The observer of the [`AuditRecord`]s of the errors in the
+    /// [`Config::audit_domains`]. Default: none.
+    ///
+    pub audit_observer: Option<AuditObserver>,
+}
+
+impl Config {
+    ///
+    /// Override settings with the values of the environment variables documented
+    /// on [`Config`], as looked up by `var`.
+    ///
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) {
+        match var("HTTP_WHATEVER_MODE").as_deref() {
+            Some("debug") => self.mode = Mode::Debug,
+            Some("prod" | "production") => self.mode = Mode::Production,
+            _ => {}
+        }
+        match var("HTTP_WHATEVER_FORMAT").as_deref() {
+            Some("empty") => self.format = ResponseFormat::Empty,
+            Some("text") => self.format = ResponseFormat::Text,
+            Some("json") => self.format = ResponseFormat::Json,
+            _ => {}
+        }
+        match var("HTTP_WHATEVER_BACKTRACE").as_deref() {
+            Some("off") => self.backtrace_style = BacktraceStyle::Off,
+            Some("full") => self.backtrace_style = BacktraceStyle::Full,
+            Some("short") => {
+                self.backtrace_style = BacktraceStyle::Short;
+                self.filter_backtrace = true;
+            }
+            _ => {}
+        }
+        if let Some(Ok(frames)) = var("HTTP_WHATEVER_BACKTRACE_FRAMES").map(|f| f.parse()) {
+            self.max_backtrace_frames = Some(frames);
+        }
+    }
+}
+
+fn cell() -> &'static RwLock<Arc<Config>> {
+    static CONFIG: OnceLock<RwLock<Arc<Config>>> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        let mut config = Config::default();
+        config.apply_env(|name| std::env::var(name).ok());
+        RwLock::new(Arc::new(config))
+    })
+}
+
+///
+/// Change the process-wide settings.
+///
+/// ```
+/// use std::sync::Arc;
+/// use http_whatever::{config, StandardRedactor};
+///
+/// config::configure(|config| config.redactor = Some(Arc::new(StandardRedactor)));
+/// ```
+///
+pub fn configure(f: impl FnOnce(&mut Config)) {
+    let mut guard = cell().write().unwrap_or_else(|e| e.into_inner());
+    let mut config = Config::clone(&guard);
+    f(&mut config);
+    *guard = Arc::new(config);
+}
+
+///
+/// Register an enricher, to be invoked on the [`ErrorContext`] of every error
+/// when it is created, so that every error carries deployment context without
+/// per-call-site plumbing.
+///
+/// ```
+/// use http_whatever::config;
+///
+/// config::add_enricher(|context| context.insert_metadata("version", env!("CARGO_PKG_VERSION")));
+/// ```
+///
+pub fn add_enricher(enricher: fn(&mut ErrorContext)) {
+    configure(|config| config.enrichers.push(enricher));
+}
+
+///
+/// Emit the [`AuditRecord`] of every error in one of the given `domains` to
+/// `observer` when a response is rendered for it - to feed a SIEM pipeline with
+/// authentication failures, for example.
+///
+/// ```
+/// use http_whatever::config;
+///
+/// config::audit(["Auth"], |record| eprintln!("{}", record.to_json()));
+/// ```
+///
+pub fn audit<I, S>(domains: I, observer: impl Fn(&AuditRecord) + Send + Sync + 'static)
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let domains: Vec<String> = domains.into_iter().map(Into::into).collect();
+    let observer: AuditObserver = Arc::new(observer);
+    configure(|config| {
+        config.audit_domains = domains;
+        config.audit_observer = Some(observer);
+    });
+}
+
+///
+/// Return a snapshot of the current process-wide settings.
+///
+pub fn current() -> Arc<Config> {
+    cell().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BacktraceStyle, Config, Mode, ResponseFormat};
+
+    #[test]
+    fn environment() {
+        let mut config = Config::default();
+        config.apply_env(|name| match name {
+            "HTTP_WHATEVER_MODE" => Some("debug".to_owned()),
+            "HTTP_WHATEVER_FORMAT" => Some("yaml".to_owned()),
+            "HTTP_WHATEVER_BACKTRACE" => Some("short".to_owned()),
+            "HTTP_WHATEVER_BACKTRACE_FRAMES" => Some("12".to_owned()),
+            _ => None,
+        });
+
+        assert_eq!(config.mode, Mode::Debug);
+        assert_eq!(config.format, ResponseFormat::Json);
+        assert_eq!(config.backtrace_style, BacktraceStyle::Short);
+        assert!(config.filter_backtrace);
+        assert_eq!(config.max_backtrace_frames, Some(12));
+    }
+}
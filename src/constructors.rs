@@ -0,0 +1,85 @@
+//!
+//! Constructors of errors of the commonly used statuses.
+//!
+use http::StatusCode;
+
+use crate::{Domain, HttpWhatever};
+
+///
+/// Implement a constructor of errors with the given status, documented with
+/// the given status line.
+///
+macro_rules! constructor {
+    ($name:ident, $status:ident, $line:literal) => {
+        impl HttpWhatever {
+            ///
+            #[doc = concat!("Create a `", $line, "` error in the given `domain`.")]
+            ///
+            pub fn $name(domain: impl Domain, message: impl std::fmt::Display) -> Self {
+                Self::categorized(StatusCode::$status, domain.as_domain(), message, None)
+            }
+        }
+    };
+}
+
+constructor!(bad_request, BAD_REQUEST, "400 Bad Request");
+constructor!(unauthorized, UNAUTHORIZED, "401 Unauthorized");
+constructor!(forbidden, FORBIDDEN, "403 Forbidden");
+constructor!(not_found, NOT_FOUND, "404 Not Found");
+constructor!(
+    method_not_allowed,
+    METHOD_NOT_ALLOWED,
+    "405 Method Not Allowed"
+);
+constructor!(conflict, CONFLICT, "409 Conflict");
+constructor!(gone, GONE, "410 Gone");
+constructor!(
+    payload_too_large,
+    PAYLOAD_TOO_LARGE,
+    "413 Payload Too Large"
+);
+constructor!(
+    unprocessable,
+    UNPROCESSABLE_ENTITY,
+    "422 Unprocessable Entity"
+);
+constructor!(internal, INTERNAL_SERVER_ERROR, "500 Internal Server Error");
+constructor!(not_implemented, NOT_IMPLEMENTED, "501 Not Implemented");
+constructor!(bad_gateway, BAD_GATEWAY, "502 Bad Gateway");
+constructor!(
+    service_unavailable,
+    SERVICE_UNAVAILABLE,
+    "503 Service Unavailable"
+);
+constructor!(gateway_timeout, GATEWAY_TIMEOUT, "504 Gateway Timeout");
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpWhatever;
+    use http::StatusCode;
+
+    #[test]
+    fn constructors() {
+        let id = 42;
+        let error = HttpWhatever::not_found("Catalog", format_args!("No item {id}"));
+        assert_eq!(
+            error.parts(),
+            ("No item 42", "Catalog", StatusCode::NOT_FOUND)
+        );
+        assert!(HttpWhatever::unprocessable("Orders", "Bad quantity")
+            .is(StatusCode::UNPROCESSABLE_ENTITY, "Orders"));
+        assert!(HttpWhatever::service_unavailable("Billing", "Ledger down")
+            .is(StatusCode::SERVICE_UNAVAILABLE, "Billing"));
+    }
+
+    #[test]
+    fn domain_with_colon() {
+        let error = HttpWhatever::not_found("api:v2", "gone");
+        assert_eq!(error.parts(), ("gone", "api_v2", StatusCode::NOT_FOUND));
+        let error = HttpWhatever::conflict(String::from("db:main"), "Duplicate key");
+        assert_eq!(
+            error.parts(),
+            ("Duplicate key", "db_main", StatusCode::CONFLICT)
+        );
+    }
+}
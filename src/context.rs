@@ -0,0 +1,252 @@
+//!
+//! Implicit, per-instance data carried alongside the error message.
+//!
+use core::fmt::{self, Debug};
+use std::collections::BTreeMap;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use http::HeaderMap;
+use snafu::GenerateImplicitData;
+
+use crate::config::{self, Config};
+use crate::{ErrorId, FieldError, RuntimeContext};
+
+///
+/// A message whose formatting is deferred until it is first needed.
+///
+pub(crate) struct LazyMessage {
+    format: Box<dyn Fn() -> String + Send + Sync>,
+    formatted: OnceLock<String>,
+}
+
+impl LazyMessage {
+    pub(crate) fn new(format: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        Self {
+            format: Box::new(format),
+            formatted: OnceLock::new(),
+        }
+    }
+
+    pub(crate) fn get(&self) -> &str {
+        self.formatted.get_or_init(|| (self.format)())
+    }
+}
+
+impl Debug for LazyMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.formatted.get() {
+            Some(message) => Debug::fmt(message, f),
+            None => f.write_str("<not yet formatted>"),
+        }
+    }
+}
+
+///
+/// Data captured automatically whenever an [`HttpWhatever`](crate::HttpWhatever)
+/// is created, however it is created.
+///
+/// Applications can add to it at creation time by registering enrichers with
+/// [`config::add_enricher`].
+///
+#[derive(Debug)]
+pub struct ErrorContext {
+    id: ErrorId,
+    created: SystemTime,
+    lazy_message: Option<LazyMessage>,
+    metadata: BTreeMap<String, String>,
+    retry_after: Option<Duration>,
+    headers: HeaderMap,
+    locale: Option<String>,
+    field_errors: Vec<FieldError>,
+    runtime: Option<RuntimeContext>,
+    location: Option<&'static Location<'static>>,
+    #[cfg(feature = "serde")]
+    data: Option<serde_json::Value>,
+    rendered: AtomicBool,
+}
+
+impl ErrorContext {
+    ///
+    /// The unique identifier of this error instance.
+    ///
+    pub fn id(&self) -> &ErrorId {
+        &self.id
+    }
+
+    ///
+    /// When the error was created.
+    ///
+    pub fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    ///
+    /// Arbitrary key/value metadata attached to the error - positional
+    /// information from a parser, for example.
+    ///
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    ///
+    /// Add (or replace) an item of metadata.
+    ///
+    pub fn insert_metadata(&mut self, key: impl Into<String>, value: impl ToString) {
+        self.metadata.insert(key.into(), value.to_string());
+    }
+
+    ///
+    /// How long the client should wait before retrying, if known.
+    ///
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    ///
+    /// Set how long the client should wait before retrying.
+    ///
+    pub fn set_retry_after(&mut self, retry_after: Duration) {
+        self.retry_after = Some(retry_after);
+    }
+
+    ///
+    /// Additional headers to emit on the HTTP responses generated from the error.
+    ///
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    ///
+    /// Mutable access to the additional response headers.
+    ///
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    ///
+    /// The language tag (`en`, `fr-CA`...) of the message, if it was localized.
+    ///
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    ///
+    /// Set the language tag of the message.
+    ///
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.locale = Some(locale.into());
+    }
+
+    ///
+    /// The field-level validation failures behind the error.
+    ///
+    pub fn field_errors(&self) -> &[FieldError] {
+        &self.field_errors
+    }
+
+    ///
+    /// Add a field-level validation failure.
+    ///
+    pub fn push_field_error(&mut self, error: FieldError) {
+        self.field_errors.push(error);
+    }
+
+    ///
+    /// The runtime context the error was created in, if its capture is enabled.
+    ///
+    pub fn runtime(&self) -> Option<&RuntimeContext> {
+        self.runtime.as_ref()
+    }
+
+    ///
+    /// Where in the source the error was created, if it was created by one of
+    /// the construction macros of this crate.
+    ///
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        self.location
+    }
+
+    ///
+    /// Structured data attached to the error, embedded under `data` by the JSON
+    /// renderers.
+    ///
+    #[cfg(feature = "serde")]
+    pub fn data(&self) -> Option<&serde_json::Value> {
+        self.data.as_ref()
+    }
+
+    ///
+    /// Attach structured data to the error.
+    ///
+    #[cfg(feature = "serde")]
+    pub fn set_data(&mut self, data: serde_json::Value) {
+        self.data = Some(data);
+    }
+
+    pub(crate) fn set_id(&mut self, id: ErrorId) {
+        self.id = id;
+    }
+
+    pub(crate) fn set_location(&mut self, location: &'static Location<'static>) {
+        self.location = Some(location);
+    }
+
+    pub(crate) fn lazy_message(&self) -> Option<&str> {
+        self.lazy_message.as_ref().map(LazyMessage::get)
+    }
+
+    pub(crate) fn set_lazy_message(&mut self, message: LazyMessage) {
+        self.lazy_message = Some(message);
+    }
+
+    pub(crate) fn clear_lazy_message(&mut self) {
+        self.lazy_message = None;
+    }
+}
+
+impl ErrorContext {
+    ///
+    /// Capture the context of a new error under the settings of `config`.
+    ///
+    pub(crate) fn generate_with(config: &Config) -> Self {
+        let mut context = Self {
+            id: ErrorId::generate(),
+            created: SystemTime::now(),
+            lazy_message: None,
+            metadata: BTreeMap::new(),
+            retry_after: None,
+            headers: HeaderMap::new(),
+            locale: None,
+            field_errors: Vec::new(),
+            runtime: config.capture_runtime_context.then(RuntimeContext::capture),
+            location: None,
+            #[cfg(feature = "serde")]
+            data: None,
+            rendered: AtomicBool::new(false),
+        };
+        #[cfg(feature = "tracing")]
+        crate::span::capture(&mut context);
+        for enricher in &config.enrichers {
+            enricher(&mut context);
+        }
+        context
+    }
+}
+
+impl ErrorContext {
+    ///
+    /// Mark the error as rendered, returning whether it had not been before.
+    ///
+    pub(crate) fn first_render(&self) -> bool {
+        !self.rendered.swap(true, Ordering::Relaxed)
+    }
+}
+
+impl GenerateImplicitData for ErrorContext {
+    fn generate() -> Self {
+        Self::generate_with(&config::current())
+    }
+}
@@ -0,0 +1,130 @@
+//!
+//! A fluent builder converting a foreign error into an [`HttpWhatever`].
+//!
+use std::error::Error;
+
+use http::StatusCode;
+
+use crate::{Domain, HttpWhatever};
+
+///
+/// Converts a foreign error into an [`HttpWhatever`], created by
+/// [`HttpWhatever::from_error`].
+///
+/// Unless set otherwise, the status is the [`Domain::default_status`] of the
+/// domain or else `500 Internal Server Error`, the domain is "unknown" and the
+/// message is the `to_string()` output of the error, which is kept as the
+/// source.
+///
+/// ```rust
+/// use http::StatusCode;
+/// use http_whatever::HttpWhatever;
+///
+/// let error = HttpWhatever::from_error("x".parse::<u8>().unwrap_err())
+///     .status(502)
+///     .domain("Upstream")
+///     .message("The upstream sent a bad count")
+///     .finish();
+///
+/// assert!(error.is(StatusCode::BAD_GATEWAY, "Upstream"));
+/// ```
+///
+#[derive(Debug)]
+#[must_use = "call `finish` to create the HttpWhatever"]
+pub struct ErrorConversion<E> {
+    error: E,
+    status: Option<StatusCode>,
+    default_status: Option<StatusCode>,
+    domain: String,
+    message: Option<String>,
+}
+
+impl<E> ErrorConversion<E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    ///
+    /// Set the status of the error. An invalid status leaves the status unchanged.
+    ///
+    pub fn status(mut self, status: impl TryInto<StatusCode>) -> Self {
+        if let Ok(status) = status.try_into() {
+            self.status = Some(status);
+        }
+        self
+    }
+
+    ///
+    /// Set the domain of the error.
+    ///
+    pub fn domain(mut self, domain: impl Domain) -> Self {
+        self.domain = domain.as_domain().to_owned();
+        self.default_status = domain.default_status();
+        self
+    }
+
+    ///
+    /// Set the message of the error.
+    ///
+    pub fn message(mut self, message: impl std::fmt::Display) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    ///
+    /// Create the [`HttpWhatever`].
+    ///
+    pub fn finish(self) -> HttpWhatever {
+        let status = self
+            .status
+            .or(self.default_status)
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let message = self.message.unwrap_or_else(|| self.error.to_string());
+        HttpWhatever::categorized(status, &self.domain, message, Some(Box::new(self.error)))
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// Start converting `error` into an `HttpWhatever` - see [`ErrorConversion`].
+    ///
+    pub fn from_error<E>(error: E) -> ErrorConversion<E>
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        ErrorConversion {
+            error,
+            status: None,
+            default_status: None,
+            domain: "unknown".to_owned(),
+            message: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpWhatever;
+    use http::StatusCode;
+    use std::num::ParseIntError;
+
+    #[test]
+    fn conversion() {
+        let error = HttpWhatever::from_error("x".parse::<u8>().unwrap_err()).finish();
+        assert_eq!(
+            error.parts(),
+            (
+                "invalid digit found in string",
+                "unknown",
+                StatusCode::INTERNAL_SERVER_ERROR
+            )
+        );
+        assert!(error.chain_contains::<ParseIntError>());
+
+        let error = HttpWhatever::from_error("x".parse::<u8>().unwrap_err())
+            .status(StatusCode::BAD_REQUEST)
+            .status(1000)
+            .domain("Input")
+            .finish();
+        assert!(error.is(StatusCode::BAD_REQUEST, "Input"));
+    }
+}
@@ -0,0 +1,145 @@
+//!
+//! Conversions from AWS SDK errors.
+//!
+use std::error::Error;
+
+use ::aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+use ::aws_smithy_runtime_api::client::result::SdkError;
+use ::aws_smithy_types::error::metadata::ProvideErrorMetadata;
+use http::StatusCode;
+
+use crate::{HttpWhatever, UPSTREAM_BODY_LIMIT};
+
+///
+/// Service error codes the AWS services use to signal throttling.
+///
+const THROTTLING_CODES: [&str; 12] = [
+    "Throttling",
+    "ThrottlingException",
+    "ThrottledException",
+    "RequestThrottledException",
+    "TooManyRequestsException",
+    "ProvisionedThroughputExceededException",
+    "TransactionInProgressException",
+    "RequestLimitExceeded",
+    "BandwidthLimitExceeded",
+    "LimitExceededException",
+    "RequestThrottled",
+    "PriorRequestNotComplete",
+];
+
+///
+/// Service error codes the AWS services use to ask clients to back off.
+///
+const UNAVAILABLE_CODES: [&str; 2] = ["SlowDown", "ServiceUnavailable"];
+
+///
+/// A failed AWS SDK call, domain "Aws":
+///
+/// * Throttling service errors - `429` (or `503` for `SlowDown`/`ServiceUnavailable`)
+/// * Other service errors - the HTTP status of the service response
+/// * Timeouts - `504`
+/// * Construction, dispatch and response failures - `502`
+///
+/// The body of a service error response is recorded, up to
+/// [`UPSTREAM_BODY_LIMIT`] bytes, as the `upstream.body` metadata.
+///
+impl<E> From<SdkError<E, HttpResponse>> for HttpWhatever
+where
+    E: ProvideErrorMetadata + Error + Send + Sync + 'static,
+{
+    fn from(error: SdkError<E, HttpResponse>) -> Self {
+        let status = match &error {
+            SdkError::TimeoutError(_) => StatusCode::GATEWAY_TIMEOUT,
+            SdkError::ServiceError(service_error) => match service_error.err().code() {
+                Some(code) if THROTTLING_CODES.contains(&code) => StatusCode::TOO_MANY_REQUESTS,
+                Some(code) if UNAVAILABLE_CODES.contains(&code) => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::from_u16(service_error.raw().status().as_u16())
+                    .unwrap_or(StatusCode::BAD_GATEWAY),
+            },
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        let message = match (error.code(), error.message()) {
+            (Some(code), Some(message)) => format!("{code}: {message}"),
+            (Some(code), None) => code.to_owned(),
+            (None, Some(message)) => message.to_owned(),
+            (None, None) => error.to_string(),
+        };
+        let body = match &error {
+            SdkError::ServiceError(service_error) => {
+                let raw = service_error.raw();
+                raw.body().bytes().map(|body| {
+                    (
+                        body.to_vec(),
+                        raw.headers().get("content-type").map(str::to_owned),
+                    )
+                })
+            }
+            _ => None,
+        };
+        let error = HttpWhatever::categorized(status, "Aws", message, Some(Box::new(error)));
+        match body {
+            Some((body, content_type)) => {
+                error.with_upstream_body(body, content_type.as_deref(), UPSTREAM_BODY_LIMIT)
+            }
+            None => error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+    use ::aws_smithy_runtime_api::client::result::SdkError;
+    use ::aws_smithy_runtime_api::http::StatusCode as AwsStatusCode;
+    use ::aws_smithy_types::body::SdkBody;
+    use ::aws_smithy_types::error::ErrorMetadata;
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    fn service_error(status: u16, code: &str) -> HttpWhatever {
+        let error = ErrorMetadata::builder()
+            .code(code)
+            .message("Refused")
+            .build();
+        let raw = HttpResponse::new(
+            AwsStatusCode::try_from(status).unwrap(),
+            SdkBody::from("<Error>Refused</Error>"),
+        );
+        HttpWhatever::from(SdkError::service_error(error, raw))
+    }
+
+    #[test]
+    fn sdk_errors() {
+        let error = service_error(400, "ThrottlingException");
+        assert_eq!(
+            error.parts(),
+            (
+                "ThrottlingException: Refused",
+                "Aws",
+                StatusCode::TOO_MANY_REQUESTS
+            )
+        );
+        assert!(error.metadata()["upstream.body"].contains("Refused"));
+        assert_eq!(
+            service_error(503, "SlowDown").parts().2,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            service_error(404, "NoSuchKey").parts().2,
+            StatusCode::NOT_FOUND
+        );
+
+        let timeout = SdkError::<ErrorMetadata, HttpResponse>::timeout_error("timed out");
+        assert_eq!(
+            HttpWhatever::from(timeout).parts().2,
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        let failure = SdkError::<ErrorMetadata, HttpResponse>::construction_failure("bad input");
+        assert_eq!(
+            HttpWhatever::from(failure).parts().2,
+            StatusCode::BAD_GATEWAY
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//!
+//! Conversions from [`axum`](::axum) extractor rejections.
+//!
+use ::axum::extract::rejection::{
+    ExtensionRejection, FormRejection, JsonRejection, PathRejection, QueryRejection,
+};
+
+use crate::HttpWhatever;
+
+///
+/// Implement `From<$rejection>` for `HttpWhatever`, with the status axum gives
+/// the rejection, the given domain and the body text of the rejection as the
+/// message.
+///
+macro_rules! from_rejection {
+    ($rejection:ty, $domain:literal) => {
+        impl From<$rejection> for HttpWhatever {
+            fn from(rejection: $rejection) -> Self {
+                HttpWhatever::categorized(
+                    rejection.status(),
+                    $domain,
+                    rejection.body_text(),
+                    Some(Box::new(rejection)),
+                )
+            }
+        }
+    };
+}
+
+// An unparsable JSON body - `400`, `415` or `422` as axum decides, domain
+// "RequestContent".
+from_rejection!(JsonRejection, "RequestContent");
+// An unparsable form body - `400`, `415` or `422`, domain "RequestContent".
+from_rejection!(FormRejection, "RequestContent");
+// An undeserializable query string - `400`, domain "Query".
+from_rejection!(QueryRejection, "Query");
+// Undeserializable path parameters - `400` (or `500` for a misconfigured
+// route), domain "Path".
+from_rejection!(PathRejection, "Path");
+// A missing request extension - `500`, domain "Internal".
+from_rejection!(ExtensionRejection, "Internal");
+
+#[cfg(test)]
+mod tests {
+    use ::axum::body::Body;
+    use ::axum::extract::{Extension, FromRequest, FromRequestParts, Json, Query};
+    use http::{Request, StatusCode};
+
+    use crate::integrations::testing::block_on;
+    use crate::HttpWhatever;
+
+    #[test]
+    fn rejections() {
+        let rejection =
+            block_on(Json::<u8>::from_request(Request::new(Body::from("1")), &())).unwrap_err();
+        let error = HttpWhatever::from(rejection);
+        assert!(error.is(StatusCode::UNSUPPORTED_MEDIA_TYPE, "RequestContent"));
+
+        let rejection = Query::<u8>::try_from_uri(&"/?x".parse().unwrap()).unwrap_err();
+        let error = HttpWhatever::from(rejection);
+        assert!(error.is(StatusCode::BAD_REQUEST, "Query"));
+
+        let (mut parts, _) = Request::new(Body::empty()).into_parts();
+        let rejection = block_on(Extension::<u8>::from_request_parts(&mut parts, &())).unwrap_err();
+        let error = HttpWhatever::from(rejection);
+        assert!(error.is(StatusCode::INTERNAL_SERVER_ERROR, "Internal"));
+    }
+}
@@ -0,0 +1,67 @@
+//!
+//! Conversions from [`csv`](::csv) errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A failure to read CSV content - `400`, domain "RequestContent" (or `500`,
+/// domain "Internal", for I/O failures).
+///
+/// Where the error has a position, the `csv.line`, `csv.record` and `csv.byte`
+/// metadata items are set from it, and `csv.field` is set to the index of the
+/// offending field of a record that could not be deserialized.
+///
+impl From<::csv::Error> for HttpWhatever {
+    fn from(error: ::csv::Error) -> Self {
+        let (status, domain) = match error.kind() {
+            ::csv::ErrorKind::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal"),
+            _ => (StatusCode::BAD_REQUEST, "RequestContent"),
+        };
+        let position = error.position().cloned();
+        let field = match error.kind() {
+            ::csv::ErrorKind::Deserialize { err, .. } => err.field(),
+            _ => None,
+        };
+
+        let mut converted =
+            HttpWhatever::categorized(status, domain, error.to_string(), Some(Box::new(error)));
+        if let Some(position) = position {
+            converted = converted
+                .with_metadata("csv.line", position.line())
+                .with_metadata("csv.record", position.record())
+                .with_metadata("csv.byte", position.byte());
+        }
+        if let Some(field) = field {
+            converted = converted.with_metadata("csv.field", field);
+        }
+        converted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::csv::ReaderBuilder;
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn csv_errors() {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader("1\nx\n".as_bytes());
+        let error = reader.deserialize::<(u8,)>().nth(1).unwrap().unwrap_err();
+        let error = HttpWhatever::from(error);
+        assert!(error.is(StatusCode::BAD_REQUEST, "RequestContent"));
+        assert_eq!(error.metadata()["csv.line"], "2");
+        assert_eq!(error.metadata()["csv.record"], "1");
+        assert_eq!(error.metadata()["csv.field"], "0");
+
+        let error = ::csv::Error::from(std::io::Error::other("disk on fire"));
+        let error = HttpWhatever::from(error);
+        assert!(error.is(StatusCode::INTERNAL_SERVER_ERROR, "Internal"));
+        assert!(error.metadata().is_empty());
+    }
+}
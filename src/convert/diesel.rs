@@ -0,0 +1,81 @@
+//!
+//! Conversions from [`diesel`](::diesel) errors.
+//!
+use ::diesel::result::{DatabaseErrorKind, Error};
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A failed database operation, domain "db":
+///
+/// * No row returned by a query expecting one - `404`
+/// * A unique or foreign key constraint violation - `409`
+/// * A serialization failure of a transaction - `503`, as it can be retried
+/// * Anything else - `500`
+///
+/// The messages are generic, so that no SQL or schema detail reaches the
+/// client; the database error is kept as the source.
+///
+impl From<Error> for HttpWhatever {
+    fn from(error: Error) -> Self {
+        let (status, message) = match &error {
+            Error::NotFound => (StatusCode::NOT_FOUND, "No matching row"),
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                (StatusCode::CONFLICT, "Unique constraint violated")
+            }
+            Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+                (StatusCode::CONFLICT, "Foreign key constraint violated")
+            }
+            Error::DatabaseError(DatabaseErrorKind::SerializationFailure, _) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Transaction could not be serialized",
+            ),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        };
+        HttpWhatever::categorized(status, "db", message, Some(Box::new(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::diesel::result::{DatabaseErrorKind, Error};
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    fn database_error(kind: DatabaseErrorKind) -> HttpWhatever {
+        HttpWhatever::from(Error::DatabaseError(
+            kind,
+            Box::new(String::from("violates constraint \"orders_pkey\"")),
+        ))
+    }
+
+    #[test]
+    fn diesel_errors() {
+        assert_eq!(
+            HttpWhatever::from(Error::NotFound).parts(),
+            ("No matching row", "db", StatusCode::NOT_FOUND)
+        );
+        assert_eq!(
+            database_error(DatabaseErrorKind::UniqueViolation).parts(),
+            ("Unique constraint violated", "db", StatusCode::CONFLICT)
+        );
+        assert_eq!(
+            database_error(DatabaseErrorKind::ForeignKeyViolation)
+                .parts()
+                .2,
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            database_error(DatabaseErrorKind::SerializationFailure)
+                .parts()
+                .2,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            HttpWhatever::from(Error::RollbackTransaction).parts(),
+            ("Database error", "db", StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//!
+//! Conversions from [`governor`](::governor) rate-limiting outcomes.
+//!
+use ::governor::clock::{Clock, DefaultClock};
+use ::governor::{InsufficientCapacity, NotUntil};
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A rate-limited request - a [`HttpWhatever::rate_limited`] error whose
+/// `Retry-After` is the time until the limiter would allow it.
+///
+/// ```ignore
+/// limiter.check().map_err(HttpWhatever::from)?;
+/// ```
+///
+impl From<NotUntil<<DefaultClock as Clock>::Instant>> for HttpWhatever {
+    fn from(not_until: NotUntil<<DefaultClock as Clock>::Instant>) -> Self {
+        let wait = not_until.wait_time_from(DefaultClock::default().now());
+        HttpWhatever::rate_limited(wait)
+    }
+}
+
+///
+/// A batch of cells larger than the rate limiter could ever allow - `429`,
+/// domain "RateLimit", with no `Retry-After` since waiting will not help.
+///
+impl From<InsufficientCapacity> for HttpWhatever {
+    fn from(error: InsufficientCapacity) -> Self {
+        HttpWhatever::categorized(
+            StatusCode::TOO_MANY_REQUESTS,
+            "RateLimit",
+            error.to_string(),
+            Some(Box::new(error)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use ::governor::{InsufficientCapacity, Quota, RateLimiter};
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn rate_limits() {
+        let limiter = RateLimiter::direct(Quota::per_hour(NonZeroU32::new(1).unwrap()));
+        assert!(limiter.check().is_ok());
+        let error = HttpWhatever::from(limiter.check().unwrap_err());
+        assert!(error.is(StatusCode::TOO_MANY_REQUESTS, "RateLimit"));
+        assert!(error.retry_after().is_some());
+
+        let error = HttpWhatever::from(InsufficientCapacity(10));
+        assert!(error.is(StatusCode::TOO_MANY_REQUESTS, "RateLimit"));
+        assert_eq!(error.retry_after(), None);
+    }
+}
@@ -0,0 +1,27 @@
+//!
+//! Conversions from [`hyper`](::hyper) errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A failed HTTP exchange, domain "transport" - `500` for errors caused by
+/// local misuse of hyper, `502` for anything else: a malformed or incomplete
+/// message from the peer, or a closed or canceled connection.
+///
+impl From<::hyper::Error> for HttpWhatever {
+    fn from(error: ::hyper::Error) -> Self {
+        let status = if error.is_user() {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::BAD_GATEWAY
+        };
+        HttpWhatever::categorized(
+            status,
+            "transport",
+            error.to_string(),
+            Some(Box::new(error)),
+        )
+    }
+}
@@ -0,0 +1,98 @@
+//!
+//! Conversions from [`jsonwebtoken`](::jsonwebtoken) errors.
+//!
+use ::jsonwebtoken::errors::{Error, ErrorKind};
+use http::header::WWW_AUTHENTICATE;
+use http::{HeaderValue, StatusCode};
+
+use crate::HttpWhatever;
+
+///
+/// A rejected JSON Web Token, domain "auth":
+///
+/// * A malformed, expired or not yet valid token, a bad signature or invalid
+///   claims - `401`, with an RFC 6750 `WWW-Authenticate: Bearer
+///   error="invalid_token"` challenge whose `error_description` is the message
+/// * A problem with the keys or cryptography of the server - `500`
+///
+impl From<Error> for HttpWhatever {
+    fn from(error: Error) -> Self {
+        let message = match error.kind() {
+            ErrorKind::InvalidEcdsaKey
+            | ErrorKind::InvalidRsaKey(_)
+            | ErrorKind::RsaFailedSigning
+            | ErrorKind::InvalidKeyFormat
+            | ErrorKind::Crypto(_) => {
+                return HttpWhatever::categorized(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "auth",
+                    "Token verification is misconfigured",
+                    Some(Box::new(error)),
+                );
+            }
+            ErrorKind::ExpiredSignature => "The token has expired",
+            ErrorKind::ImmatureSignature => "The token is not yet valid",
+            ErrorKind::InvalidSignature => "The token signature is invalid",
+            ErrorKind::InvalidIssuer
+            | ErrorKind::InvalidAudience
+            | ErrorKind::InvalidSubject
+            | ErrorKind::MissingRequiredClaim(_) => "The token claims are invalid",
+            _ => "The token is invalid",
+        };
+        let challenge = format!("Bearer error=\"invalid_token\", error_description=\"{message}\"");
+        let converted = HttpWhatever::categorized(
+            StatusCode::UNAUTHORIZED,
+            "auth",
+            message,
+            Some(Box::new(error)),
+        );
+        match HeaderValue::from_str(&challenge) {
+            Ok(challenge) => converted.with_header(WWW_AUTHENTICATE, challenge),
+            Err(_) => converted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::jsonwebtoken::errors::{Error, ErrorKind};
+    use http::header::WWW_AUTHENTICATE;
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn token_errors() {
+        let error = HttpWhatever::from(Error::from(ErrorKind::ExpiredSignature));
+        assert_eq!(
+            error.parts(),
+            ("The token has expired", "auth", StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            error.response_headers()[WWW_AUTHENTICATE],
+            "Bearer error=\"invalid_token\", error_description=\"The token has expired\""
+        );
+        assert_eq!(
+            HttpWhatever::from(Error::from(ErrorKind::InvalidSignature))
+                .parts()
+                .0,
+            "The token signature is invalid"
+        );
+        assert_eq!(
+            HttpWhatever::from(Error::from(ErrorKind::InvalidAudience))
+                .parts()
+                .0,
+            "The token claims are invalid"
+        );
+        assert_eq!(
+            HttpWhatever::from(Error::from(ErrorKind::InvalidToken))
+                .parts()
+                .0,
+            "The token is invalid"
+        );
+
+        let error = HttpWhatever::from(Error::from(ErrorKind::InvalidEcdsaKey));
+        assert!(error.is(StatusCode::INTERNAL_SERVER_ERROR, "auth"));
+        assert!(error.response_headers().get(WWW_AUTHENTICATE).is_none());
+    }
+}
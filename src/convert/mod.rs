@@ -0,0 +1,212 @@
+//!
+//! Conversions from common error types into [`HttpWhatever`], so that `?` can
+//! be used directly on them.
+//!
+use std::io::{self, ErrorKind};
+use std::str::Utf8Error;
+use std::string::FromUtf8Error;
+
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+#[cfg(feature = "aws")]
+mod aws;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "governor")]
+mod governor;
+#[cfg(feature = "hyper")]
+mod hyper;
+#[cfg(feature = "jsonwebtoken")]
+mod jsonwebtoken;
+#[cfg(feature = "multer")]
+mod multer;
+#[cfg(any(feature = "serde_qs", feature = "serde_urlencoded"))]
+mod query;
+#[cfg(feature = "reqwest")]
+mod reqwest;
+#[cfg(feature = "serde_json")]
+mod serde_json;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(feature = "tower")]
+mod tower;
+#[cfg(feature = "url")]
+mod url;
+#[cfg(feature = "validator")]
+mod validator;
+
+///
+/// Undecodable request bytes - `400`, domain "RequestContent".
+///
+impl From<Utf8Error> for HttpWhatever {
+    fn from(error: Utf8Error) -> Self {
+        HttpWhatever::categorized(
+            StatusCode::BAD_REQUEST,
+            "RequestContent",
+            "Invalid UTF-8 content",
+            Some(Box::new(error)),
+        )
+    }
+}
+
+///
+/// Undecodable request bytes - `400`, domain "RequestContent".
+///
+impl From<FromUtf8Error> for HttpWhatever {
+    fn from(error: FromUtf8Error) -> Self {
+        HttpWhatever::categorized(
+            StatusCode::BAD_REQUEST,
+            "RequestContent",
+            "Invalid UTF-8 content",
+            Some(Box::new(error)),
+        )
+    }
+}
+
+///
+/// A failed I/O operation, domain "io":
+///
+/// * `NotFound` - `404`
+/// * `PermissionDenied` - `403`
+/// * `AlreadyExists` - `409`
+/// * `TimedOut` - `504`
+/// * Anything else - `500`
+///
+impl From<io::Error> for HttpWhatever {
+    fn from(error: io::Error) -> Self {
+        let status = match error.kind() {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+            ErrorKind::AlreadyExists => StatusCode::CONFLICT,
+            ErrorKind::TimedOut => StatusCode::GATEWAY_TIMEOUT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        HttpWhatever::categorized(status, "io", error.to_string(), Some(Box::new(error)))
+    }
+}
+
+///
+/// A bare status, with its canonical reason as the message - domain "unknown".
+///
+impl From<StatusCode> for HttpWhatever {
+    fn from(status: StatusCode) -> Self {
+        HttpWhatever::categorized(
+            status,
+            "unknown",
+            status.canonical_reason().unwrap_or("Unknown status"),
+            None,
+        )
+    }
+}
+
+///
+/// A status and message - domain "unknown".
+///
+impl From<(StatusCode, &str)> for HttpWhatever {
+    fn from((status, message): (StatusCode, &str)) -> Self {
+        HttpWhatever::categorized(status, "unknown", message, None)
+    }
+}
+
+///
+/// A status, domain and message.
+///
+impl From<(StatusCode, &str, &str)> for HttpWhatever {
+    fn from((status, domain, message): (StatusCode, &str, &str)) -> Self {
+        HttpWhatever::categorized(status, domain, message, None)
+    }
+}
+
+///
+/// A numeric status and message - domain "unknown". An invalid status is `500`.
+///
+impl From<(u16, String)> for HttpWhatever {
+    fn from((status, message): (u16, String)) -> Self {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        HttpWhatever::categorized(status, "unknown", message, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use http::StatusCode;
+
+    fn decode(bytes: Vec<u8>) -> HttpResult<String> {
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    #[test]
+    fn utf8_errors() {
+        let error = decode(vec![0xff, 0xfe]).unwrap_err();
+        let parts = error.parts();
+
+        assert_eq!(parts.0, "Invalid UTF-8 content");
+        assert_eq!(parts.1, "RequestContent");
+        assert_eq!(parts.2, StatusCode::BAD_REQUEST);
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn io_errors() {
+        use std::io::{Error, ErrorKind};
+
+        fn open(kind: ErrorKind) -> HttpResult<()> {
+            Err(Error::new(kind, "open failed"))?
+        }
+
+        let error = open(ErrorKind::NotFound).unwrap_err();
+        assert_eq!(error.parts(), ("open failed", "io", StatusCode::NOT_FOUND));
+        assert!(error.chain_contains::<Error>());
+        assert_eq!(
+            open(ErrorKind::PermissionDenied).unwrap_err().parts().2,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            open(ErrorKind::UnexpectedEof).unwrap_err().parts().2,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn bare_status() {
+        fn forbidden() -> HttpResult<()> {
+            Err(StatusCode::FORBIDDEN)?
+        }
+
+        assert_eq!(
+            forbidden().unwrap_err().parts(),
+            ("Forbidden", "unknown", StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[test]
+    fn tuples() {
+        assert_eq!(
+            HttpWhatever::from((StatusCode::GONE, "Retired")).parts(),
+            ("Retired", "unknown", StatusCode::GONE)
+        );
+        assert_eq!(
+            HttpWhatever::from((StatusCode::CONFLICT, "Orders", "Already shipped")).parts(),
+            ("Already shipped", "Orders", StatusCode::CONFLICT)
+        );
+        assert_eq!(
+            HttpWhatever::from((429, format!("Slow down, {}", "client"))).parts(),
+            (
+                "Slow down, client",
+                "unknown",
+                StatusCode::TOO_MANY_REQUESTS
+            )
+        );
+        assert_eq!(
+            HttpWhatever::from((42, String::from("Odd"))).parts().2,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//!
+//! Conversions from [`multer`](::multer) errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A failure to parse a multipart upload - `413` when a size limit was exceeded,
+/// otherwise `400`, domain "Upload".
+///
+impl From<::multer::Error> for HttpWhatever {
+    fn from(error: ::multer::Error) -> Self {
+        let status = match error {
+            ::multer::Error::FieldSizeExceeded { .. }
+            | ::multer::Error::StreamSizeExceeded { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        HttpWhatever::categorized(status, "Upload", error.to_string(), Some(Box::new(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn multipart_errors() {
+        let error = HttpWhatever::from(::multer::Error::FieldSizeExceeded {
+            limit: 1024,
+            field_name: Some("avatar".to_owned()),
+        });
+        assert!(error.is(StatusCode::PAYLOAD_TOO_LARGE, "Upload"));
+        let error = HttpWhatever::from(::multer::Error::StreamSizeExceeded { limit: 1024 });
+        assert!(error.is(StatusCode::PAYLOAD_TOO_LARGE, "Upload"));
+        let error = HttpWhatever::from(::multer::Error::IncompleteStream);
+        assert!(error.is(StatusCode::BAD_REQUEST, "Upload"));
+    }
+}
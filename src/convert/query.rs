@@ -0,0 +1,59 @@
+//!
+//! Conversions from query-string deserialization errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// An undeserializable query string - `400`, domain "Query".
+///
+#[cfg(feature = "serde_urlencoded")]
+impl From<::serde_urlencoded::de::Error> for HttpWhatever {
+    fn from(error: ::serde_urlencoded::de::Error) -> Self {
+        HttpWhatever::categorized(
+            StatusCode::BAD_REQUEST,
+            "Query",
+            error.to_string(),
+            Some(Box::new(error)),
+        )
+    }
+}
+
+///
+/// An undeserializable query string - `400`, domain "Query".
+///
+#[cfg(feature = "serde_qs")]
+impl From<::serde_qs::Error> for HttpWhatever {
+    fn from(error: ::serde_qs::Error) -> Self {
+        HttpWhatever::categorized(
+            StatusCode::BAD_REQUEST,
+            "Query",
+            error.to_string(),
+            Some(Box::new(error)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[cfg(feature = "serde_urlencoded")]
+    #[test]
+    fn urlencoded_errors() {
+        let error = ::serde_urlencoded::from_str::<HashMap<String, u8>>("page=x").unwrap_err();
+        assert!(HttpWhatever::from(error).is(StatusCode::BAD_REQUEST, "Query"));
+    }
+
+    #[cfg(feature = "serde_qs")]
+    #[test]
+    fn qs_errors() {
+        let error = ::serde_qs::from_str::<HashMap<String, u8>>("page=x").unwrap_err();
+        assert!(HttpWhatever::from(error).is(StatusCode::BAD_REQUEST, "Query"));
+    }
+}
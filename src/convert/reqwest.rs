@@ -0,0 +1,73 @@
+//!
+//! Conversions from [`reqwest`](::reqwest) errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A failed request to an upstream service, domain "upstream":
+///
+/// * An error status returned by the upstream - that status
+/// * A failure to connect, or a timeout - `504`
+/// * Any other transport failure - `502`
+///
+impl From<::reqwest::Error> for HttpWhatever {
+    fn from(error: ::reqwest::Error) -> Self {
+        let (status, message) = match error.status() {
+            Some(status) => (status, format!("Upstream responded with {status}")),
+            None if error.is_timeout() => (
+                StatusCode::GATEWAY_TIMEOUT,
+                "Upstream request timed out".to_owned(),
+            ),
+            None if error.is_connect() => (
+                StatusCode::GATEWAY_TIMEOUT,
+                "Could not connect to upstream".to_owned(),
+            ),
+            None => (
+                StatusCode::BAD_GATEWAY,
+                "Upstream request failed".to_owned(),
+            ),
+        };
+        HttpWhatever::categorized(status, "upstream", message, Some(Box::new(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn upstream_errors() {
+        let response = http::Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body("")
+            .unwrap();
+        let error = ::reqwest::Response::from(response)
+            .error_for_status()
+            .unwrap_err();
+        assert_eq!(
+            HttpWhatever::from(error).parts(),
+            (
+                "Upstream responded with 404 Not Found",
+                "upstream",
+                StatusCode::NOT_FOUND
+            )
+        );
+
+        let error = ::reqwest::Client::new()
+            .get("not a url")
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            HttpWhatever::from(error).parts(),
+            (
+                "Upstream request failed",
+                "upstream",
+                StatusCode::BAD_GATEWAY
+            )
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//!
+//! Conversions from [`serde_json`](::serde_json) errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A failure to read JSON content - `400`, domain "RequestContent" (or `500`,
+/// domain "Internal", for I/O failures) - with the serde message.
+///
+/// The `json.line` and `json.column` metadata items are set from the position
+/// of the error, where it has one.
+///
+impl From<::serde_json::Error> for HttpWhatever {
+    fn from(error: ::serde_json::Error) -> Self {
+        let (status, domain) = if error.is_io() {
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal")
+        } else {
+            (StatusCode::BAD_REQUEST, "RequestContent")
+        };
+        let (line, column) = (error.line(), error.column());
+
+        let converted =
+            HttpWhatever::categorized(status, domain, error.to_string(), Some(Box::new(error)));
+        if line == 0 {
+            converted
+        } else {
+            converted
+                .with_metadata("json.line", line)
+                .with_metadata("json.column", column)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn json_errors() {
+        let error = ::serde_json::from_str::<u8>("\n  x").unwrap_err();
+        let error = HttpWhatever::from(error);
+        assert!(error.is(StatusCode::BAD_REQUEST, "RequestContent"));
+        assert_eq!(error.metadata()["json.line"], "2");
+        assert_eq!(error.metadata()["json.column"], "3");
+
+        let error = ::serde_json::Error::io(std::io::Error::other("disk on fire"));
+        let error = HttpWhatever::from(error);
+        assert!(error.is(StatusCode::INTERNAL_SERVER_ERROR, "Internal"));
+        assert!(error.metadata().is_empty());
+    }
+}
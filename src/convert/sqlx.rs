@@ -0,0 +1,109 @@
+//!
+//! Conversions from [`sqlx`](::sqlx) errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// A failed database operation, domain "db":
+///
+/// * No row returned by a query expecting one - `404`
+/// * A unique constraint violation - `409`
+/// * A timeout waiting for a pooled connection - `503`
+/// * Anything else - `500`
+///
+/// The messages are generic, so that no SQL or schema detail reaches the
+/// client; the database error is kept as the source.
+///
+impl From<::sqlx::Error> for HttpWhatever {
+    fn from(error: ::sqlx::Error) -> Self {
+        let (status, message) = match &error {
+            ::sqlx::Error::RowNotFound => (StatusCode::NOT_FOUND, "No matching row"),
+            ::sqlx::Error::Database(database) if database.is_unique_violation() => {
+                (StatusCode::CONFLICT, "Unique constraint violated")
+            }
+            ::sqlx::Error::PoolTimedOut => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Timed out waiting for a database connection",
+            ),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
+        };
+        HttpWhatever::categorized(status, "db", message, Some(Box::new(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::fmt::{self, Display};
+
+    use ::sqlx::error::{DatabaseError, ErrorKind};
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    ///
+    /// A database error, a unique constraint violation or not.
+    ///
+    #[derive(Debug)]
+    struct Violation {
+        unique: bool,
+    }
+
+    impl Display for Violation {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("duplicate key value violates unique constraint")
+        }
+    }
+
+    impl Error for Violation {}
+
+    impl DatabaseError for Violation {
+        fn message(&self) -> &str {
+            "duplicate key value violates unique constraint"
+        }
+
+        fn as_error(&self) -> &(dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            if self.unique {
+                ErrorKind::UniqueViolation
+            } else {
+                ErrorKind::Other
+            }
+        }
+    }
+
+    #[test]
+    fn sqlx_errors() {
+        assert_eq!(
+            HttpWhatever::from(::sqlx::Error::RowNotFound).parts(),
+            ("No matching row", "db", StatusCode::NOT_FOUND)
+        );
+        let violation = ::sqlx::Error::Database(Box::new(Violation { unique: true }));
+        assert_eq!(
+            HttpWhatever::from(violation).parts(),
+            ("Unique constraint violated", "db", StatusCode::CONFLICT)
+        );
+        assert_eq!(
+            HttpWhatever::from(::sqlx::Error::PoolTimedOut).parts().2,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        let other = ::sqlx::Error::Database(Box::new(Violation { unique: false }));
+        assert_eq!(
+            HttpWhatever::from(other).parts(),
+            ("Database error", "db", StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+}
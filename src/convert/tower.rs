@@ -0,0 +1,75 @@
+//!
+//! Conversions from [`tower`](::tower) errors.
+//!
+use ::tower::load_shed::error::Overloaded;
+use ::tower::timeout::error::Elapsed;
+use ::tower::BoxError;
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+impl HttpWhatever {
+    ///
+    /// Create an error from one emerging from a tower stack:
+    ///
+    /// * An `HttpWhatever` is passed through unchanged
+    /// * A timeout ([`Elapsed`]) - `504`, domain "Timeout"
+    /// * A load-shedding rejection ([`Overloaded`]) - `503`, domain "Overloaded"
+    /// * Anything else - `500`, domain "Internal", message "Internal error"
+    ///
+    /// The error is kept as the source, but only the message of an
+    /// `HttpWhatever` reaches clients. This is a named function rather than a
+    /// `From` implementation because `BoxError` is any boxed error - converting
+    /// it implicitly would make every `?` on a boxed error a `500`.
+    ///
+    pub fn from_box_error(error: BoxError) -> Self {
+        let error = match error.downcast::<HttpWhatever>() {
+            Ok(error) => return *error,
+            Err(error) => error,
+        };
+        let (status, domain, message) = if error.is::<Elapsed>() {
+            (StatusCode::GATEWAY_TIMEOUT, "Timeout", error.to_string())
+        } else if error.is::<Overloaded>() {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Overloaded",
+                error.to_string(),
+            )
+        } else {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal",
+                "Internal error".to_owned(),
+            )
+        };
+        HttpWhatever::categorized(status, domain, message, Some(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::tower::load_shed::error::Overloaded;
+    use ::tower::timeout::error::Elapsed;
+    use ::tower::BoxError;
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn box_errors() {
+        let error = HttpWhatever::from_box_error(BoxError::from(Elapsed::new()));
+        assert!(error.is(StatusCode::GATEWAY_TIMEOUT, "Timeout"));
+        let error = HttpWhatever::from_box_error(BoxError::from(Overloaded::new()));
+        assert!(error.is(StatusCode::SERVICE_UNAVAILABLE, "Overloaded"));
+        let error = HttpWhatever::from_box_error(BoxError::from("boom"));
+        assert!(error.is(StatusCode::INTERNAL_SERVER_ERROR, "Internal"));
+        assert_eq!(error.parts().0, "Internal error");
+
+        let inner = HttpWhatever::not_found("Catalog", "No such item");
+        let error = HttpWhatever::from_box_error(BoxError::from(inner));
+        assert_eq!(
+            error.parts(),
+            ("No such item", "Catalog", StatusCode::NOT_FOUND)
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//!
+//! Conversions from [`url`](::url) errors.
+//!
+use http::StatusCode;
+
+use crate::HttpWhatever;
+
+///
+/// An unparsable URL received in a request - `400`, domain "RequestContent".
+///
+/// Use [`HttpWhatever::from_server_url_error`] for URLs constructed by the
+/// server itself, where a parse failure is a bug rather than bad input.
+///
+impl From<::url::ParseError> for HttpWhatever {
+    fn from(error: ::url::ParseError) -> Self {
+        HttpWhatever::categorized(
+            StatusCode::BAD_REQUEST,
+            "RequestContent",
+            "Invalid URL",
+            Some(Box::new(error)),
+        )
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// Create a `500` error, domain "Internal", from the failure to parse a URL
+    /// constructed by the server itself.
+    ///
+    pub fn from_server_url_error(error: ::url::ParseError) -> Self {
+        HttpWhatever::categorized(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal",
+            "Invalid server-constructed URL",
+            Some(Box::new(error)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn url_errors() {
+        let error = ::url::Url::parse("not a url").unwrap_err();
+        assert_eq!(
+            HttpWhatever::from(error).parts(),
+            ("Invalid URL", "RequestContent", StatusCode::BAD_REQUEST)
+        );
+        assert!(HttpWhatever::from_server_url_error(error)
+            .is(StatusCode::INTERNAL_SERVER_ERROR, "Internal"));
+    }
+}
@@ -0,0 +1,96 @@
+//!
+//! Conversions from [`validator`](::validator) errors.
+//!
+use ::validator::{ValidationErrors, ValidationErrorsKind};
+use http::StatusCode;
+
+use crate::{FieldError, HttpWhatever};
+
+///
+/// Collect the field errors of `errors`, nested under the `path` segments.
+///
+fn collect(errors: &ValidationErrors, path: &mut Vec<String>, out: &mut Vec<FieldError>) {
+    let mut fields: Vec<_> = errors.errors().iter().collect();
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    for (field, kind) in fields {
+        path.push(field.to_string());
+        match kind {
+            ValidationErrorsKind::Field(failures) => {
+                for failure in failures {
+                    let message = failure.message.as_deref().unwrap_or(&*failure.code);
+                    out.push(FieldError::at(
+                        path.iter(),
+                        failure.code.to_string(),
+                        message,
+                    ));
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => collect(nested, path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    path.push(index.to_string());
+                    collect(nested, path, out);
+                    path.pop();
+                }
+            }
+        }
+        path.pop();
+    }
+}
+
+///
+/// A request document that failed validation - `422`, domain "Validation" -
+/// with a [`FieldError`] for every violation, located by the JSON pointer of
+/// its field, so that the JSON renderers emit the per-field violations rather
+/// than a flattened string. The message of a violation without one is its code.
+///
+impl From<ValidationErrors> for HttpWhatever {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut fields = Vec::new();
+        collect(&errors, &mut Vec::new(), &mut fields);
+        fields.into_iter().fold(
+            HttpWhatever::categorized(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Validation",
+                "Validation failed",
+                Some(Box::new(errors)),
+            ),
+            HttpWhatever::with_field_error,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use ::validator::{ValidationError, ValidationErrors};
+    use http::StatusCode;
+
+    use crate::HttpWhatever;
+
+    #[test]
+    fn validation_errors() {
+        let mut errors = ValidationErrors::new();
+        errors.add("quantity", ValidationError::new("range"));
+        errors.add(
+            "name",
+            ValidationError::new("length").with_message(Cow::from("Too long")),
+        );
+        let error = HttpWhatever::from(errors);
+
+        assert!(error.is(StatusCode::UNPROCESSABLE_ENTITY, "Validation"));
+        let fields: Vec<_> = error
+            .field_errors()
+            .iter()
+            .map(|field| (field.pointer(), field.code(), field.message()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("/name", "length", "Too long"),
+                ("/quantity", "range", "range")
+            ]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//!
+//! Dead-letter records for message-queue workers.
+//!
+use std::error::Error;
+use std::time::SystemTime;
+
+use crate::date::format_rfc3339;
+use crate::json::{self, JsonObject};
+use crate::{render_text, HttpWhatever};
+
+impl HttpWhatever {
+    ///
+    /// Return a self-contained JSON record of the error, suitable for parking a
+    /// failed job on a Kafka, NATS or SQS dead-letter queue:
+    ///
+    /// ```json
+    /// {
+    ///   "id": "<error id>",
+    ///   "status": 503,
+    ///   "domain": "<domain>",
+    ///   "message": "<message>",
+    ///   "chain": ["<source>", ...],
+    ///   "metadata": {"<key>": "<value>", ...},
+    ///   "created_at": "<RFC 3339 timestamp of the error>",
+    ///   "dead_lettered_at": "<RFC 3339 timestamp of this call>",
+    ///   "attempts": 3
+    /// }
+    /// ```
+    ///
+    /// The message and the source chain are redacted as for responses.
+    ///
+    pub fn as_dead_letter_payload(&self, attempts: u32) -> String {
+        self.dead_letter_payload_at(attempts, SystemTime::now())
+    }
+
+    fn dead_letter_payload_at(&self, attempts: u32, now: SystemTime) -> String {
+        let parts = self.parts();
+        let mut chain = Vec::new();
+        let mut source = self.source();
+        while let Some(e) = source {
+            chain.push(json::string(&render_text(self.id(), &e.to_string())));
+            source = e.source();
+        }
+        let mut metadata = JsonObject::new();
+        for (key, value) in self.metadata() {
+            metadata.string(key, value);
+        }
+        JsonObject::new()
+            .string("id", self.id().as_str())
+            .number("status", parts.2.as_u16())
+            .string("domain", parts.1)
+            .string("message", &self.rendered_message())
+            .raw("chain", &json::array(chain))
+            .raw("metadata", &metadata.finish())
+            .string("created_at", &format_rfc3339(self.context().created()))
+            .string("dead_lettered_at", &format_rfc3339(now))
+            .number("attempts", attempts)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn dead_letter_payload() {
+        let error = Err::<(), _>(HttpWhatever::new("503:Billing:Ledger unavailable"))
+            .whatever_context::<_, HttpWhatever>("Job failed")
+            .unwrap_err()
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+            .with_metadata("job", 42);
+        let payload = error.dead_letter_payload_at(3, UNIX_EPOCH + Duration::from_secs(784111777));
+
+        assert!(payload.starts_with(
+            r#"{"id":"01ARZ3NDEKTSV4RRFFQ69G5FAV","status":500,"domain":"Internal","message":"Job failed","chain":["Ledger unavailable: (Domain: Billing, HTTP status: 503 Service Unavailable)"],"metadata":{"job":"42"},"created_at":""#
+        ));
+        assert!(
+            payload.ends_with(r#"","dead_lettered_at":"1994-11-06T08:49:37.000Z","attempts":3}"#)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//!
+//! Per-request debug responses.
+//!
+use http::{Extensions, Response};
+
+use crate::{BacktraceStyle, HttpError, HttpWhatever};
+
+///
+/// A marker that, in the `Extensions` of a request, makes
+/// [`HttpWhatever::as_http_json_response_for`] include the
+/// [`details`](HttpWhatever::details) and full backtrace of the error in the
+/// response to that request only.
+///
+/// The marker is also honoured by the `http_whatever_error_handlers` of the
+/// `actix-web` feature and the `http_whatever_debug_responses` middleware of the
+/// `axum` feature. The other framework integrations have no access to the
+/// request when rendering an error, and ignore it.
+///
+/// Only insert it from trusted middleware - after verifying an internal debug
+/// header or administrator credentials, for example - so that production
+/// defaults stay safe while deep debugging remains available on demand.
+///
+/// ```
+/// use http::{Request, Response};
+/// use http_whatever::{DebugResponses, HttpWhatever};
+///
+/// let mut request = Request::new(());
+/// request.extensions_mut().insert(DebugResponses);
+///
+/// let error = HttpWhatever::new("500:Billing:Ledger unavailable");
+/// let response: Response<String> = error.as_http_json_response_for(request.extensions());
+/// assert!(response.body().contains("\"details\""));
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DebugResponses;
+
+impl HttpWhatever {
+    ///
+    /// As [`HttpWhatever::as_http_json_response`], but with the details and full
+    /// backtrace of the error if the request `extensions` hold the
+    /// [`DebugResponses`] marker.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_json_response_for<B>(&self, extensions: &Extensions) -> Response<B>
+    where
+        B: From<String>,
+    {
+        self.as_http_json_response_debugging(extensions.get::<DebugResponses>().is_some())
+    }
+
+    ///
+    /// As [`HttpWhatever::as_http_json_response`], but with the details and full
+    /// backtrace of the error if `debug` is set, as for a request holding the
+    /// [`DebugResponses`] marker.
+    ///
+    pub(crate) fn as_http_json_response_debugging<B>(&self, debug: bool) -> Response<B>
+    where
+        B: From<String>,
+    {
+        let debug = if debug {
+            Some(BacktraceStyle::Full)
+        } else {
+            crate::debug_style()
+        };
+        (self as &dyn HttpError)
+            .json_response_builder()
+            .body(self.json_body_for(debug).into())
+            .expect("Response::build should succeed")
+    }
+}
@@ -0,0 +1,196 @@
+//!
+//! Typed error domains.
+//!
+use std::borrow::Cow;
+
+use http::StatusCode;
+
+///
+/// An application domain of errors. Implementing it for an enum of the domains
+/// of an application checks them at compile time, while string slices remain
+/// usable for quick use.
+///
+/// With the `derive` feature, it can be derived, along with `Display`, with
+/// `#[derive(HttpDomain)]` - giving each variant a domain name (the name of the
+/// variant unless set with `#[http_domain(name = "...")]`) and, optionally, a
+/// default status (`#[http_domain(status = 404)]`).
+///
+/// Domains are accepted by the typed constructors,
+/// [`HttpWhatever::from_err_with`](crate::HttpWhatever::from_err_with),
+/// [`HttpWhatever::builder`](crate::HttpWhatever::builder),
+/// [`HttpWhatever::from_error`](crate::HttpWhatever::from_error),
+/// [`HttpWhatever::with_domain`](crate::HttpWhatever::with_domain), the
+/// [`HttpResultExt`](crate::HttpResultExt) contexts, the
+/// [`GatewayMap`](crate::GatewayMap) helpers and the construction macros.
+///
+/// ```
+/// use http::StatusCode;
+/// use http_whatever::{Domain, HttpWhatever};
+///
+/// enum Shop {
+///     Catalog,
+///     Orders,
+/// }
+///
+/// impl Domain for Shop {
+///     fn as_domain(&self) -> &str {
+///         match self {
+///             Shop::Catalog => "Catalog",
+///             Shop::Orders => "Orders",
+///         }
+///     }
+/// }
+///
+/// let error = HttpWhatever::not_found(Shop::Catalog, "No such item");
+/// assert!(error.is(StatusCode::NOT_FOUND, "Catalog"));
+/// ```
+///
+pub trait Domain {
+    ///
+    /// The name of the domain, as rendered in responses. It should not contain
+    /// a `:`, which separates the parts of the message of an error - any `:` is
+    /// replaced by `_` when the domain is given to an error.
+    ///
+    fn as_domain(&self) -> &str;
+
+    ///
+    /// The status of the errors of the domain, where none is given explicitly -
+    /// by [`HttpWhatever::builder`](crate::HttpWhatever::builder), for example.
+    /// Default: none.
+    ///
+    fn default_status(&self) -> Option<StatusCode> {
+        None
+    }
+}
+
+impl Domain for str {
+    fn as_domain(&self) -> &str {
+        self
+    }
+}
+
+impl Domain for String {
+    fn as_domain(&self) -> &str {
+        self
+    }
+}
+
+impl<D> Domain for &D
+where
+    D: Domain + ?Sized,
+{
+    fn as_domain(&self) -> &str {
+        (**self).as_domain()
+    }
+
+    fn default_status(&self) -> Option<StatusCode> {
+        (**self).default_status()
+    }
+}
+
+///
+/// `domain` with any `:` replaced by `_`, so that it cannot corrupt the
+/// `"status:domain:message"` form of the message of an error.
+///
+pub(crate) fn sanitized(domain: &str) -> Cow<'_, str> {
+    if domain.contains(':') {
+        Cow::Owned(domain.replace(':', "_"))
+    } else {
+        Cow::Borrowed(domain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Domain;
+    use crate::prelude::*;
+    use http::StatusCode;
+
+    enum Bank {
+        Ledger,
+        Transfers,
+    }
+
+    impl Domain for Bank {
+        fn as_domain(&self) -> &str {
+            match self {
+                Bank::Ledger => "Ledger",
+                Bank::Transfers => "Transfers",
+            }
+        }
+
+        fn default_status(&self) -> Option<StatusCode> {
+            match self {
+                Bank::Ledger => None,
+                Bank::Transfers => Some(StatusCode::CONFLICT),
+            }
+        }
+    }
+
+    #[test]
+    fn typed_domains() {
+        let error = http_whatever!(503, Bank::Ledger, "Ledger unavailable");
+        assert!(error.is(StatusCode::SERVICE_UNAVAILABLE, "Ledger"));
+
+        let error = HttpWhatever::builder().domain(Bank::Ledger).build();
+        assert!(error.is(StatusCode::INTERNAL_SERVER_ERROR, "Ledger"));
+
+        let domain = String::from("Payments");
+        let error = error.with_domain(&domain);
+        assert!(error.is(StatusCode::INTERNAL_SERVER_ERROR, "Payments"));
+    }
+
+    #[test]
+    fn typed_error_domains() {
+        let parse_error = || "x".parse::<u8>().unwrap_err();
+
+        let error = HttpWhatever::from_error(parse_error())
+            .domain(Bank::Transfers)
+            .finish();
+        assert!(error.is(StatusCode::CONFLICT, "Transfers"));
+        let error = HttpWhatever::from_error(parse_error())
+            .status(502)
+            .domain(Bank::Transfers)
+            .finish();
+        assert!(error.is(StatusCode::BAD_GATEWAY, "Transfers"));
+
+        let error =
+            HttpWhatever::from_err_with(StatusCode::BAD_REQUEST, Bank::Ledger, parse_error());
+        assert!(error.is(StatusCode::BAD_REQUEST, "Ledger"));
+
+        let error = "x"
+            .parse::<u8>()
+            .http_context(StatusCode::BAD_REQUEST, Bank::Ledger, "Bad amount")
+            .unwrap_err();
+        assert!(error.is(StatusCode::BAD_REQUEST, "Ledger"));
+        let error = "x"
+            .parse::<u8>()
+            .with_http_context(StatusCode::BAD_REQUEST, Bank::Transfers, || "Bad amount")
+            .unwrap_err();
+        assert!(error.is(StatusCode::BAD_REQUEST, "Transfers"));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_domains() {
+        use crate::HttpDomain;
+
+        #[derive(HttpDomain)]
+        enum Shop {
+            Catalog,
+            #[http_domain(name = "Order-Management", status = 409)]
+            Orders,
+        }
+
+        assert_eq!(Shop::Catalog.as_domain(), "Catalog");
+        assert_eq!(Shop::Catalog.default_status(), None);
+        assert_eq!(Shop::Orders.to_string(), "Order-Management");
+        assert_eq!(Shop::Orders.default_status(), Some(StatusCode::CONFLICT));
+
+        let error = HttpWhatever::new(http_err!(404, Shop::Catalog, "No such item"));
+        assert!(error.is(StatusCode::NOT_FOUND, "Catalog"));
+        let error = HttpWhatever::builder().domain(Shop::Orders).build();
+        assert!(error.is(StatusCode::CONFLICT, "Order-Management"));
+        assert_eq!(error.parts().0, "Conflict");
+    }
+}
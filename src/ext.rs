@@ -0,0 +1,221 @@
+//!
+//! Extension traits for results.
+//!
+use core::fmt::Display;
+use std::error::Error;
+
+use http::StatusCode;
+
+use crate::{Domain, HttpResult, HttpWhatever};
+
+///
+/// Extensions to [`Result`] for producing [`HttpWhatever`] errors, complementing
+/// the `whatever_context` family from [`snafu::ResultExt`].
+///
+pub trait HttpResultExt<T> {
+    ///
+    /// As `with_whatever_context`, except that the closure is only called, and so
+    /// the message only formatted, if and when the error is actually rendered or
+    /// inspected. See [`HttpWhatever::new_lazy`].
+    ///
+    fn whatever_lazy_context<F>(self, message: F) -> HttpResult<T>
+    where
+        F: Fn() -> String + Send + Sync + 'static;
+
+    ///
+    /// As `whatever_context`, but with the status, domain and message as
+    /// separate, typed arguments rather than one structured string.
+    ///
+    /// ```rust
+    /// use http::StatusCode;
+    /// use http_whatever::prelude::*;
+    ///
+    /// let error = "x"
+    ///     .parse::<u32>()
+    ///     .http_context(StatusCode::BAD_REQUEST, "Input", "Bad count")
+    ///     .unwrap_err();
+    ///
+    /// assert!(error.is(StatusCode::BAD_REQUEST, "Input"));
+    /// ```
+    ///
+    fn http_context<D, M>(self, status: StatusCode, domain: D, message: M) -> HttpResult<T>
+    where
+        D: Domain,
+        M: Display;
+
+    ///
+    /// As [`HttpResultExt::http_context`], except that the message is only
+    /// created, by calling `message`, if the result is an error.
+    ///
+    fn with_http_context<D, F, M>(self, status: StatusCode, domain: D, message: F) -> HttpResult<T>
+    where
+        D: Domain,
+        F: FnOnce() -> M,
+        M: Display;
+}
+
+impl<T, E> HttpResultExt<T> for Result<T, E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    fn whatever_lazy_context<F>(self, message: F) -> HttpResult<T>
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        self.map_err(|error| HttpWhatever::new_lazy_with_source(Box::new(error), message))
+    }
+
+    fn http_context<D, M>(self, status: StatusCode, domain: D, message: M) -> HttpResult<T>
+    where
+        D: Domain,
+        M: Display,
+    {
+        self.with_http_context(status, domain, || message)
+    }
+
+    fn with_http_context<D, F, M>(self, status: StatusCode, domain: D, message: F) -> HttpResult<T>
+    where
+        D: Domain,
+        F: FnOnce() -> M,
+        M: Display,
+    {
+        self.map_err(|error| {
+            HttpWhatever::categorized(status, domain.as_domain(), message(), Some(Box::new(error)))
+        })
+    }
+}
+
+///
+/// Combinators for [`HttpResult`]s overriding the status of their error, when
+/// internal failures are translated at API boundaries.
+///
+/// ```rust
+/// use http::StatusCode;
+/// use http_whatever::prelude::*;
+///
+/// fn find(id: u32) -> HttpResult<String> {
+///     Err(HttpWhatever::new(format!("500:Db:No row for {id}")))
+/// }
+///
+/// let error = find(7).or_404().unwrap_err();
+/// assert!(error.is(StatusCode::NOT_FOUND, "Db"));
+/// ```
+///
+pub trait StatusExt<T> {
+    ///
+    /// Override the status of the error, if any - see
+    /// [`HttpWhatever::with_status`].
+    ///
+    fn or_status(self, status: StatusCode) -> HttpResult<T>;
+
+    ///
+    /// Override the status of the error, if any, with `400 Bad Request`.
+    ///
+    fn or_400(self) -> HttpResult<T>
+    where
+        Self: Sized,
+    {
+        self.or_status(StatusCode::BAD_REQUEST)
+    }
+
+    ///
+    /// Override the status of the error, if any, with `403 Forbidden`.
+    ///
+    fn or_403(self) -> HttpResult<T>
+    where
+        Self: Sized,
+    {
+        self.or_status(StatusCode::FORBIDDEN)
+    }
+
+    ///
+    /// Override the status of the error, if any, with `404 Not Found`.
+    ///
+    fn or_404(self) -> HttpResult<T>
+    where
+        Self: Sized,
+    {
+        self.or_status(StatusCode::NOT_FOUND)
+    }
+
+    ///
+    /// Override the status of the error, if any, with `409 Conflict`.
+    ///
+    fn or_409(self) -> HttpResult<T>
+    where
+        Self: Sized,
+    {
+        self.or_status(StatusCode::CONFLICT)
+    }
+
+    ///
+    /// Override the status of the error, if any, with `502 Bad Gateway`.
+    ///
+    fn or_502(self) -> HttpResult<T>
+    where
+        Self: Sized,
+    {
+        self.or_status(StatusCode::BAD_GATEWAY)
+    }
+
+    ///
+    /// Override the status of the error, if any, with `503 Service Unavailable`.
+    ///
+    fn or_503(self) -> HttpResult<T>
+    where
+        Self: Sized,
+    {
+        self.or_status(StatusCode::SERVICE_UNAVAILABLE)
+    }
+}
+
+impl<T> StatusExt<T> for HttpResult<T> {
+    fn or_status(self, status: StatusCode) -> HttpResult<T> {
+        self.map_err(|error| error.with_status(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use http::StatusCode;
+    use std::num::ParseIntError;
+
+    #[test]
+    fn http_context() {
+        let error = "x"
+            .parse::<u32>()
+            .with_http_context(StatusCode::UNPROCESSABLE_ENTITY, "Orders", || {
+                format!("Bad quantity {:?}", "x")
+            })
+            .unwrap_err();
+
+        assert_eq!(
+            error.parts(),
+            (
+                "Bad quantity \"x\"",
+                "Orders",
+                StatusCode::UNPROCESSABLE_ENTITY
+            )
+        );
+        assert!(error.chain_contains::<ParseIntError>());
+        assert_eq!(
+            "7".parse::<u32>()
+                .http_context(StatusCode::BAD_REQUEST, "Input", "Bad count")
+                .unwrap(),
+            7
+        );
+    }
+
+    #[test]
+    fn status_combinators() {
+        let result: HttpResult<()> = Err(HttpWhatever::new("500:Db:Timed out"));
+        assert!(result
+            .or_503()
+            .unwrap_err()
+            .is(StatusCode::SERVICE_UNAVAILABLE, "Db"));
+
+        let result: HttpResult<u8> = Ok(1);
+        assert_eq!(result.or_status(StatusCode::GONE).unwrap(), 1);
+    }
+}
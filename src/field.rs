@@ -0,0 +1,111 @@
+//!
+//! Field-level validation errors.
+//!
+use core::fmt::Display;
+
+use crate::json::JsonObject;
+
+///
+/// A validation failure of one field of a request document, located by an
+/// [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901) JSON Pointer such as
+/// `/items/3/price`, so that clients can highlight the exact offending field.
+///
+/// ```
+/// use http_whatever::FieldError;
+///
+/// let error = FieldError::at(["items", "3", "price"], "range", "Must be positive");
+/// assert_eq!(error.pointer(), "/items/3/price");
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pointer: String,
+    code: String,
+    message: String,
+}
+
+impl FieldError {
+    ///
+    /// Create a field error for the field located by the (already-encoded) JSON
+    /// `pointer`.
+    ///
+    pub fn new(
+        pointer: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            pointer: pointer.into(),
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+
+    ///
+    /// Create a field error for the field located by the `path` segments, which
+    /// are encoded into a JSON Pointer.
+    ///
+    pub fn at<I, S>(path: I, code: impl Into<String>, message: impl Into<String>) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Display,
+    {
+        Self::new(json_pointer(path), code, message)
+    }
+
+    ///
+    /// The JSON Pointer locating the field.
+    ///
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    ///
+    /// A machine-readable code for the failure (`required`, `range`...).
+    ///
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    ///
+    /// A human-readable description of the failure.
+    ///
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        JsonObject::new()
+            .string("pointer", &self.pointer)
+            .string("code", &self.code)
+            .string("message", &self.message)
+            .finish()
+    }
+}
+
+///
+/// Encode `path` segments as an RFC 6901 JSON Pointer.
+///
+pub fn json_pointer<I, S>(path: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: Display,
+{
+    let mut pointer = String::new();
+    for segment in path {
+        pointer.push('/');
+        pointer.push_str(&segment.to_string().replace('~', "~0").replace('/', "~1"));
+    }
+    pointer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_pointer;
+
+    #[test]
+    fn pointers_are_escaped() {
+        assert_eq!(json_pointer(["a/b", "m~n"]), "/a~1b/m~0n");
+        assert_eq!(json_pointer(Vec::<String>::new()), "");
+    }
+}
@@ -0,0 +1,434 @@
+//!
+//! Translation of upstream statuses for proxies and backends-for-frontends.
+//!
+use std::collections::HashMap;
+
+use http::{HeaderMap, StatusCode};
+
+use crate::{Domain, HttpWhatever, DEFAULT_UPSTREAM_HEADERS};
+
+///
+/// A mapping from the status returned by an upstream dependency to the status a
+/// proxy or backend-for-frontend should return downstream, so that edge services
+/// express "my dependency failed" correctly rather than leaking the upstream's
+/// view of the world.
+///
+/// The default mapping is
+///
+/// * `401`, `403` - `500` (our credentials are wrong, not the client's)
+/// * `404` - `502`
+/// * `408`, `504` - `504`
+/// * `429`, `503` - passed through
+/// * anything else - `502` (the fallback)
+///
+/// ```
+/// use http::StatusCode;
+/// use http_whatever::GatewayMap;
+///
+/// let map = GatewayMap::default().map(StatusCode::NOT_FOUND, StatusCode::NOT_FOUND);
+/// assert_eq!(map.translate(StatusCode::NOT_FOUND), StatusCode::NOT_FOUND);
+/// assert_eq!(map.translate(StatusCode::UNAUTHORIZED), StatusCode::INTERNAL_SERVER_ERROR);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct GatewayMap {
+    mappings: HashMap<StatusCode, StatusCode>,
+    fallback: StatusCode,
+}
+
+impl Default for GatewayMap {
+    fn default() -> Self {
+        Self::new(StatusCode::BAD_GATEWAY)
+            .map(StatusCode::UNAUTHORIZED, StatusCode::INTERNAL_SERVER_ERROR)
+            .map(StatusCode::FORBIDDEN, StatusCode::INTERNAL_SERVER_ERROR)
+            .map(StatusCode::NOT_FOUND, StatusCode::BAD_GATEWAY)
+            .map(StatusCode::REQUEST_TIMEOUT, StatusCode::GATEWAY_TIMEOUT)
+            .map(StatusCode::GATEWAY_TIMEOUT, StatusCode::GATEWAY_TIMEOUT)
+            .pass_through(StatusCode::TOO_MANY_REQUESTS)
+            .pass_through(StatusCode::SERVICE_UNAVAILABLE)
+    }
+}
+
+impl GatewayMap {
+    ///
+    /// Create an empty mapping, in which every upstream status translates to
+    /// `fallback`.
+    ///
+    pub fn new(fallback: StatusCode) -> Self {
+        Self {
+            mappings: HashMap::new(),
+            fallback,
+        }
+    }
+
+    ///
+    /// Translate the `upstream` status to the `downstream` status.
+    ///
+    pub fn map(mut self, upstream: StatusCode, downstream: StatusCode) -> Self {
+        self.mappings.insert(upstream, downstream);
+        self
+    }
+
+    ///
+    /// Pass the `status` through unchanged.
+    ///
+    pub fn pass_through(self, status: StatusCode) -> Self {
+        self.map(status, status)
+    }
+
+    ///
+    /// Return the downstream status for the `upstream` status.
+    ///
+    pub fn translate(&self, upstream: StatusCode) -> StatusCode {
+        self.mappings
+            .get(&upstream)
+            .copied()
+            .unwrap_or(self.fallback)
+    }
+
+    ///
+    /// Return the downstream status for the `upstream` status, for an error in
+    /// `domain` - the [`Domain::default_status`] of which, if any, replaces the
+    /// fallback.
+    ///
+    fn translate_for(&self, upstream: StatusCode, domain: &impl Domain) -> StatusCode {
+        self.mappings
+            .get(&upstream)
+            .copied()
+            .or_else(|| domain.default_status())
+            .unwrap_or(self.fallback)
+    }
+
+    ///
+    /// Create an error, in the given `domain`, for an upstream response with the
+    /// given status. An upstream status without a mapping translates to the
+    /// [`Domain::default_status`] of the domain, if it has one.
+    ///
+    pub fn from_status(&self, upstream: StatusCode, domain: impl Domain) -> HttpWhatever {
+        HttpWhatever::categorized(
+            self.translate_for(upstream, &domain),
+            domain.as_domain(),
+            format!("Upstream responded with {upstream}"),
+            None,
+        )
+    }
+
+    ///
+    /// Create an error, in the given `domain`, for an upstream response with the
+    /// given status and headers, preserving the [`DEFAULT_UPSTREAM_HEADERS`] -
+    /// notably `Retry-After`, so that clients of the proxy back off correctly.
+    /// The status is translated as by [`GatewayMap::from_status`].
+    ///
+    pub fn from_response(
+        &self,
+        upstream: StatusCode,
+        headers: &HeaderMap,
+        domain: impl Domain,
+    ) -> HttpWhatever {
+        self.from_status(upstream, domain)
+            .with_upstream_headers(headers, &DEFAULT_UPSTREAM_HEADERS)
+    }
+
+    ///
+    /// Wrap an error describing an upstream failure, in the given `domain`, with
+    /// its status translated as by [`GatewayMap::from_status`]. The upstream error
+    /// is kept as the source.
+    ///
+    pub fn wrap(&self, upstream: HttpWhatever, domain: impl Domain) -> HttpWhatever {
+        let status = upstream.parts().2;
+        HttpWhatever::categorized(
+            self.translate_for(status, &domain),
+            domain.as_domain(),
+            format!("Upstream failed with {status}"),
+            Some(Box::new(upstream)),
+        )
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// Create a `502 Bad Gateway` error, in the given `domain`, for an upstream
+    /// response whose status was `received` rather than `expected`. The received
+    /// status is recorded as the `upstream.status` metadata.
+    ///
+    /// This is usually created with the [`ensure_status!`](crate::ensure_status)
+    /// macro.
+    ///
+    pub fn unexpected_status(
+        received: StatusCode,
+        expected: StatusCode,
+        domain: impl Domain,
+    ) -> Self {
+        Self::categorized(
+            StatusCode::BAD_GATEWAY,
+            domain.as_domain(),
+            format!("Unexpected upstream status {received} (expected {expected})"),
+            None,
+        )
+        .with_metadata("upstream.status", received.as_u16())
+    }
+
+    ///
+    /// Record the start of an upstream response body - lossily decoded as UTF-8 -
+    /// as the `upstream.body` metadata. This is
+    /// [`HttpWhatever::with_upstream_body`] for a text body of unknown content
+    /// type, limited to [`UPSTREAM_BODY_LIMIT`] bytes.
+    ///
+    pub fn with_body_snippet(self, body: impl AsRef<[u8]>) -> Self {
+        self.with_upstream_body(body, None, UPSTREAM_BODY_LIMIT)
+    }
+
+    ///
+    /// Record up to `max` bytes of an upstream response body as the
+    /// `upstream.body` metadata, so that operators can see what the dependency
+    /// actually said.
+    ///
+    /// Bodies with no content type, or a textual one - `text/*`, JSON, XML or
+    /// form data - are decoded as UTF-8, or as ISO-8859-1 or windows-1252 if
+    /// that is their charset, and end with `…` if truncated. Other bodies are
+    /// recorded only by their size and content type, e.g.
+    /// `<2048 bytes of image/png>`.
+    ///
+    pub fn with_upstream_body(
+        self,
+        body: impl AsRef<[u8]>,
+        content_type: Option<&str>,
+        max: usize,
+    ) -> Self {
+        let body = body.as_ref();
+        let text = match content_type.map(MediaType::parse) {
+            None => decode(body, Charset::Utf8, max),
+            Some(media) if media.is_textual() => decode(body, media.charset(), max),
+            Some(media) => format!("<{} bytes of {}>", body.len(), media.essence),
+        };
+        self.with_metadata("upstream.body", text)
+    }
+}
+
+///
+/// The default limit on the number of bytes of an upstream body recorded by
+/// the client-side helpers and integrations.
+///
+pub const UPSTREAM_BODY_LIMIT: usize = 1024;
+
+///
+/// The charsets upstream bodies are decoded from.
+///
+#[derive(Clone, Copy)]
+enum Charset {
+    Utf8,
+    Latin1,
+    Windows1252,
+}
+
+///
+/// The characters of the windows-1252 bytes `0x80` to `0x9F`, where it differs
+/// from ISO-8859-1. The five unassigned bytes map to the C1 controls, as in the
+/// WHATWG encoding standard.
+///
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+///
+/// The parts of a `Content-Type` that matter when decoding a body.
+///
+struct MediaType<'a> {
+    essence: &'a str,
+    charset: Option<&'a str>,
+}
+
+impl<'a> MediaType<'a> {
+    fn parse(content_type: &'a str) -> Self {
+        let mut params = content_type.split(';');
+        let essence = params.next().unwrap_or_default().trim();
+        let charset = params.find_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            name.trim()
+                .eq_ignore_ascii_case("charset")
+                .then(|| value.trim().trim_matches('"'))
+        });
+        Self { essence, charset }
+    }
+
+    fn is_textual(&self) -> bool {
+        let essence = self.essence.to_ascii_lowercase();
+        essence.starts_with("text/")
+            || essence.ends_with("/json")
+            || essence.ends_with("+json")
+            || essence.ends_with("/xml")
+            || essence.ends_with("+xml")
+            || essence == "application/x-www-form-urlencoded"
+    }
+
+    fn charset(&self) -> Charset {
+        match self.charset {
+            Some(charset)
+                if ["iso-8859-1", "latin1"]
+                    .iter()
+                    .any(|latin1| charset.eq_ignore_ascii_case(latin1)) =>
+            {
+                Charset::Latin1
+            }
+            Some(charset) if charset.eq_ignore_ascii_case("windows-1252") => Charset::Windows1252,
+            _ => Charset::Utf8,
+        }
+    }
+}
+
+///
+/// Decode up to `max` bytes of `body`, marking truncation with `…`.
+///
+fn decode(body: &[u8], charset: Charset, max: usize) -> String {
+    let cut = &body[..body.len().min(max)];
+    let mut text = match charset {
+        Charset::Latin1 => cut.iter().map(|&b| char::from(b)).collect(),
+        Charset::Windows1252 => cut
+            .iter()
+            .map(|&b| match b {
+                0x80..=0x9F => WINDOWS_1252[usize::from(b - 0x80)],
+                _ => char::from(b),
+            })
+            .collect(),
+        Charset::Utf8 => match std::str::from_utf8(cut) {
+            Err(e) if e.error_len().is_none() => {
+                String::from_utf8_lossy(&cut[..e.valid_up_to()]).into_owned()
+            }
+            _ => String::from_utf8_lossy(cut).into_owned(),
+        },
+    };
+    if cut.len() < body.len() {
+        text.push('…');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GatewayMap;
+    use crate::prelude::*;
+    use http::StatusCode;
+
+    #[test]
+    fn wrap_translates_status() {
+        let map = GatewayMap::default();
+        let upstream = HttpWhatever::new(http_err!(404, "Catalog", "No such item"));
+        let wrapped = map.wrap(upstream, "Upstream");
+
+        assert_eq!(wrapped.parts().2, StatusCode::BAD_GATEWAY);
+        assert_eq!(wrapped.parts().1, "Upstream");
+        assert_eq!(
+            map.from_status(StatusCode::TOO_MANY_REQUESTS, "Upstream")
+                .parts()
+                .2,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[test]
+    fn typed_domains() {
+        use crate::Domain;
+        use http::{HeaderMap, Response};
+
+        struct Payments;
+
+        impl Domain for Payments {
+            fn as_domain(&self) -> &str {
+                "Payments"
+            }
+
+            fn default_status(&self) -> Option<StatusCode> {
+                Some(StatusCode::SERVICE_UNAVAILABLE)
+            }
+        }
+
+        let map = GatewayMap::default();
+        let error = map.from_status(StatusCode::IM_A_TEAPOT, Payments);
+        assert!(error.is(StatusCode::SERVICE_UNAVAILABLE, "Payments"));
+        let error = map.wrap(error, Payments);
+        assert!(error.is(StatusCode::SERVICE_UNAVAILABLE, "Payments"));
+        let error = map.from_response(StatusCode::NOT_FOUND, &HeaderMap::new(), Payments);
+        assert!(error.is(StatusCode::BAD_GATEWAY, "Payments"));
+
+        let check = || -> crate::HttpResult<()> {
+            let response = Response::builder().status(500).body("").unwrap();
+            crate::ensure_status!(response, StatusCode::OK, Payments);
+            Ok(())
+        };
+        assert!(check().unwrap_err().is(StatusCode::BAD_GATEWAY, "Payments"));
+    }
+
+    #[test]
+    fn ensure_status() {
+        use http::Response;
+
+        fn check(response: &Response<&str>) -> crate::HttpResult<()> {
+            crate::ensure_status!(response, StatusCode::OK, "Upstream", response.body());
+            Ok(())
+        }
+
+        assert!(check(&Response::new("fine")).is_ok());
+
+        let mut response = Response::new("no such thing");
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        let error = check(&response).unwrap_err();
+        assert!(error.is(StatusCode::BAD_GATEWAY, "Upstream"));
+        assert_eq!(error.metadata()["upstream.status"], "404");
+        assert_eq!(error.metadata()["upstream.body"], "no such thing");
+    }
+
+    #[test]
+    fn upstream_bodies() {
+        let body = |body: &[u8], content_type, max| {
+            HttpWhatever::unexpected_status(StatusCode::NOT_FOUND, StatusCode::OK, "Upstream")
+                .with_upstream_body(body, content_type, max)
+                .metadata()["upstream.body"]
+                .clone()
+        };
+
+        assert_eq!(
+            body(b"{\"error\":\"gone\"}", Some("application/json"), 100),
+            "{\"error\":\"gone\"}"
+        );
+        assert_eq!(body("caf\u{e9}".as_bytes(), None, 4), "caf…");
+        assert_eq!(
+            body(b"caf\xe9", Some("text/plain; charset=ISO-8859-1"), 100),
+            "caf\u{e9}"
+        );
+        assert_eq!(
+            body(
+                b"\x93\x80 5\x94",
+                Some("text/plain; charset=windows-1252"),
+                100
+            ),
+            "\u{201C}\u{20AC} 5\u{201D}"
+        );
+        assert_eq!(
+            body(&[0x89, b'P', b'N', b'G'], Some("image/png"), 100),
+            "<4 bytes of image/png>"
+        );
+    }
+
+    #[test]
+    fn from_response_keeps_retry_after() {
+        use http::header::{HeaderMap, RETRY_AFTER};
+        use http::Response;
+        use std::time::Duration;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "30".parse().unwrap());
+        let error = GatewayMap::default().from_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &headers,
+            "Upstream",
+        );
+        let response: Response<String> = error.as_http_response();
+
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(30)));
+        assert_eq!(response.headers().get_all(RETRY_AFTER).iter().count(), 1);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "30");
+    }
+}
@@ -0,0 +1,270 @@
+//!
+//! gRPC status codes and `google.rpc` rich error details.
+//!
+//! The `google.rpc.Status` message (with `ErrorInfo`, `BadRequest` and `RetryInfo`
+//! details) is encoded by hand here - it is small and stable - so that rich error
+//! details are available without depending on a protobuf runtime.
+//!
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+use crate::config::Mode;
+use crate::HttpWhatever;
+
+///
+/// The gRPC status code trailer.
+///
+pub const GRPC_STATUS: HeaderName = HeaderName::from_static("grpc-status");
+
+///
+/// The gRPC (percent-encoded) message trailer.
+///
+pub const GRPC_MESSAGE: HeaderName = HeaderName::from_static("grpc-message");
+
+///
+/// The gRPC rich error details trailer - a base64-encoded `google.rpc.Status`.
+///
+pub const GRPC_STATUS_DETAILS_BIN: HeaderName = HeaderName::from_static("grpc-status-details-bin");
+
+const ERROR_INFO_TYPE: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+const BAD_REQUEST_TYPE: &str = "type.googleapis.com/google.rpc.BadRequest";
+const RETRY_INFO_TYPE: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+///
+/// Return the gRPC status code corresponding to an HTTP status.
+///
+pub fn grpc_code(status: StatusCode) -> i32 {
+    match status.as_u16() {
+        200..=299 => 0,  // OK
+        499 => 1,        // CANCELLED
+        400 | 422 => 3,  // INVALID_ARGUMENT
+        408 | 504 => 4,  // DEADLINE_EXCEEDED
+        404 | 410 => 5,  // NOT_FOUND
+        409 => 6,        // ALREADY_EXISTS
+        403 => 7,        // PERMISSION_DENIED
+        413 | 429 => 8,  // RESOURCE_EXHAUSTED
+        412 | 428 => 9,  // FAILED_PRECONDITION
+        416 => 11,       // OUT_OF_RANGE
+        405 | 501 => 12, // UNIMPLEMENTED
+        500 => 13,       // INTERNAL
+        502 | 503 => 14, // UNAVAILABLE
+        401 => 16,       // UNAUTHENTICATED
+        _ => 2,          // UNKNOWN
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// Return the gRPC status code corresponding to the HTTP status of the error.
+    ///
+    pub fn grpc_code(&self) -> i32 {
+        grpc_code(self.parts().2)
+    }
+
+    ///
+    /// Return the protobuf encoding of a `google.rpc.Status` describing the
+    /// error - the value of the `grpc-status-details-bin` trailer, before base64
+    /// encoding.
+    ///
+    /// The status carries the gRPC code and message, plus the following details:
+    ///
+    /// * `ErrorInfo`, with the status reason (`NOT_FOUND`...) as `reason`, the
+    ///   domain as `domain`, and the error id as `id` in `metadata`, along with
+    ///   any metadata exposed by the current [`config`](crate::config) - see
+    ///   [`Config::exposed_metadata`](crate::config::Config::exposed_metadata)
+    /// * `BadRequest`, with a field violation per field error, if there are any
+    /// * `RetryInfo`, with the retry-after duration, if there is one
+    ///
+    pub fn grpc_status_details(&self) -> Vec<u8> {
+        let parts = self.parts();
+        let message = self.rendered_message();
+
+        let mut error_info = Vec::new();
+        put_string(&mut error_info, 1, &reason(parts.2));
+        put_string(&mut error_info, 2, parts.1);
+        let config = crate::config::current();
+        let metadata = self
+            .exposed_metadata(config.mode == Mode::Debug, &config.exposed_metadata)
+            .into_iter()
+            .chain([("id", self.id().to_string())]);
+        for (key, value) in metadata {
+            let mut entry = Vec::new();
+            put_string(&mut entry, 1, key);
+            put_string(&mut entry, 2, &value);
+            put_bytes(&mut error_info, 3, &entry);
+        }
+
+        let mut status = Vec::new();
+        put_varint_field(&mut status, 1, self.grpc_code() as u64);
+        put_string(&mut status, 2, &message);
+        put_bytes(&mut status, 3, &any(ERROR_INFO_TYPE, &error_info));
+
+        if !self.field_errors().is_empty() {
+            let mut bad_request = Vec::new();
+            for field_error in self.field_errors() {
+                let mut violation = Vec::new();
+                put_string(&mut violation, 1, &field_path(field_error.pointer()));
+                put_string(&mut violation, 2, field_error.message());
+                put_bytes(&mut bad_request, 1, &violation);
+            }
+            put_bytes(&mut status, 3, &any(BAD_REQUEST_TYPE, &bad_request));
+        }
+
+        if let Some(retry_after) = self.retry_after() {
+            let mut duration = Vec::new();
+            put_varint_field(&mut duration, 1, retry_after.as_secs());
+            put_varint_field(&mut duration, 2, u64::from(retry_after.subsec_nanos()));
+            let mut retry_info = Vec::new();
+            put_bytes(&mut retry_info, 1, &duration);
+            put_bytes(&mut status, 3, &any(RETRY_INFO_TYPE, &retry_info));
+        }
+
+        status
+    }
+
+    ///
+    /// Return the gRPC trailers describing the error: `grpc-status`,
+    /// `grpc-message` and `grpc-status-details-bin`.
+    ///
+    pub fn as_grpc_trailers(&self) -> HeaderMap {
+        let mut trailers = HeaderMap::new();
+        trailers.insert(GRPC_STATUS, HeaderValue::from(self.grpc_code()));
+        if let Ok(message) = HeaderValue::from_str(&crate::percent_encode(&self.rendered_message()))
+        {
+            trailers.insert(GRPC_MESSAGE, message);
+        }
+        if let Ok(details) = HeaderValue::from_str(&base64(&self.grpc_status_details())) {
+            trailers.insert(GRPC_STATUS_DETAILS_BIN, details);
+        }
+        trailers
+    }
+}
+
+///
+/// The canonical reason of a status in `UPPER_SNAKE_CASE`, e.g. `NOT_FOUND`.
+///
+fn reason(status: StatusCode) -> String {
+    status
+        .canonical_reason()
+        .unwrap_or("UNKNOWN")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn any(type_url: &str, value: &[u8]) -> Vec<u8> {
+    let mut any = Vec::new();
+    put_string(&mut any, 1, type_url);
+    put_bytes(&mut any, 2, value);
+    any
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_varint_field(out: &mut Vec<u8>, field: u32, value: u64) {
+    if value != 0 {
+        put_varint(out, u64::from(field << 3));
+        put_varint(out, value);
+    }
+}
+
+fn put_bytes(out: &mut Vec<u8>, field: u32, value: &[u8]) {
+    put_varint(out, u64::from((field << 3) | 2));
+    put_varint(out, value.len() as u64);
+    out.extend_from_slice(value);
+}
+
+fn put_string(out: &mut Vec<u8>, field: u32, value: &str) {
+    if !value.is_empty() {
+        put_bytes(out, field, value.as_bytes());
+    }
+}
+
+///
+/// Unpadded standard base64, as emitted for gRPC binary headers.
+///
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (u32::from(chunk[0]) << 16)
+            | (u32::from(*chunk.get(1).unwrap_or(&0)) << 8)
+            | u32::from(*chunk.get(2).unwrap_or(&0));
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+///
+/// The dotted `BadRequest` field path of a JSON Pointer, with its segments
+/// unescaped.
+///
+fn field_path(pointer: &str) -> String {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, field_path, GRPC_STATUS};
+    use crate::prelude::*;
+    use crate::FieldError;
+
+    #[test]
+    fn base64_is_unpadded() {
+        assert_eq!(base64(b"M"), "TQ");
+        assert_eq!(base64(b"Ma"), "TWE");
+        assert_eq!(base64(b"Man"), "TWFu");
+    }
+
+    #[test]
+    fn field_paths() {
+        assert_eq!(field_path("/items/0/sku"), "items.0.sku");
+        assert_eq!(field_path("/a~1b/m~0n/~01"), "a/b.m~n.~1");
+        assert_eq!(field_path(""), "");
+
+        let details = HttpWhatever::new(http_err!(400, "Input", "Invalid"))
+            .with_field_error(FieldError::at(["a/b"], "required", "Missing"))
+            .grpc_status_details();
+        assert!(details.windows(3).any(|w| w == b"a/b"));
+        assert!(!details.windows(4).any(|w| w == b"a~1b"));
+    }
+
+    #[test]
+    fn status_details() {
+        let error = HttpWhatever::new(http_err!(404, "Catalog", "No such item"))
+            .with_id("X")
+            .with_metadata("user_id", "42");
+        let details = error.grpc_status_details();
+
+        assert_eq!(error.grpc_code(), 5);
+        // code 5, message "No such item", then a single ErrorInfo Any
+        assert_eq!(&details[..2], &[0x08, 0x05]);
+        assert_eq!(&details[2..4], &[0x12, 12]);
+        assert_eq!(&details[4..16], b"No such item");
+        assert_eq!(details[16], 0x1a);
+        assert!(details
+            .windows(b"NOT_FOUND".len())
+            .any(|w| w == b"NOT_FOUND"));
+        // metadata is not exposed in production mode
+        assert!(!details.windows(b"user_id".len()).any(|w| w == b"user_id"));
+        assert_eq!(error.as_grpc_trailers().get(GRPC_STATUS).unwrap(), "5");
+    }
+}
@@ -0,0 +1,83 @@
+//!
+//! HTML rendering, for browser-facing endpoints.
+//!
+use http::header::CONTENT_TYPE;
+use http::Response;
+
+use crate::{HttpError, HttpWhatever};
+
+///
+/// Escape the characters of `text` that are significant in HTML (and XML)
+/// content and attribute values.
+///
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl HttpWhatever {
+    fn html_body(&self) -> String {
+        let parts = self.parts();
+        let status = escape(&parts.2.to_string());
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{status}</title></head>\n<body>\n<h1>{status}</h1>\n<p>{}</p>\n<p>Domain: {}</p>\n</body>\n</html>\n",
+            escape(&self.rendered_message()),
+            escape(parts.1)
+        )
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with a
+    /// minimal HTML error page showing the status, message and domain - for
+    /// browser-facing endpoints, where a JSON body looks broken to end users.
+    ///
+    /// The `content-type` header of the response will be `text/html; charset=utf-8`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_html_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "text/html; charset=utf-8")
+            .body(self.html_body().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpWhatever;
+    use http::header::CONTENT_TYPE;
+    use http::Response;
+
+    #[test]
+    fn html_page() {
+        let response: Response<String> =
+            HttpWhatever::new("404:<Catalog>:No item \"a&b\"").as_http_html_response();
+
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        assert!(response.body().contains("<title>404 Not Found</title>"));
+        assert!(response
+            .body()
+            .contains("<p>No item &quot;a&amp;b&quot;</p>\n<p>Domain: &lt;Catalog&gt;</p>"));
+    }
+}
@@ -0,0 +1,408 @@
+//!
+//! The [`HttpError`] trait, through which any error type can use the response
+//! machinery of this crate.
+//!
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use http::header::CONTENT_TYPE;
+use http::response::Builder;
+use http::{Extensions, HeaderMap, Response, StatusCode};
+
+use crate::json::JsonObject;
+use crate::ErrorRecord;
+
+///
+/// An error that knows how it should be rendered as an HTTP response.
+///
+/// [`HttpWhatever`](crate::HttpWhatever) implements this trait, and other
+/// crates' typed errors can implement it to opt into the same responses - see
+/// the `as_http_*` methods of `dyn HttpError`. The trait is dyn-compatible, so
+/// integrations can work with `&dyn HttpError` whatever the concrete error.
+///
+/// Only [`HttpError::status`], [`HttpError::domain`] and [`HttpError::message`]
+/// are required; the render hooks have defaults matching the plain bodies
+/// documented on [`HttpWhatever`](crate::HttpWhatever).
+///
+/// The framework traits implemented for `HttpWhatever` - `IntoResponse` of axum,
+/// gotham and viz, `ResponseError` of actix-web, `WebResponseError` of ntex,
+/// `Responder` of rocket, `Scribe` of salvo and the `lambda_http` response
+/// conversion - are implemented for any `HttpError` wrapped in an
+/// [`HttpErrorResponse`]. The other integrations - tonic, async-graphql, warp
+/// rejections and the `as_*_response` methods of `HttpWhatever` - are specific
+/// to `HttpWhatever`; use the `as_http_*` methods of `dyn HttpError` instead.
+///
+pub trait HttpError: Error + Send + Sync {
+    ///
+    /// The HTTP status of the response.
+    ///
+    fn status(&self) -> StatusCode;
+
+    ///
+    /// The application domain of the error.
+    ///
+    fn domain(&self) -> &str;
+
+    ///
+    /// The message as it should appear in a response body.
+    ///
+    fn message(&self) -> Cow<'_, str>;
+
+    ///
+    /// Headers to add to every response rendered for the error.
+    ///
+    fn headers(&self) -> HeaderMap {
+        HeaderMap::new()
+    }
+
+    ///
+    /// Called whenever a response is rendered for the error - to emit audit
+    /// records, for example. An error rendered more than once, by a fallback or
+    /// a middleware re-rendering it, is passed here each time.
+    ///
+    fn on_render(&self) {}
+
+    ///
+    /// The body of a `text/plain` response.
+    ///
+    fn text_body(&self) -> String {
+        format!("{} (application domain: {})", self.message(), self.domain())
+    }
+
+    ///
+    /// The body of an `application/json` response.
+    ///
+    fn json_body(&self) -> String {
+        JsonObject::new()
+            .string("message", &self.message())
+            .string("domain", self.domain())
+            .finish()
+    }
+}
+
+///
+/// A wrapper rendering any [`HttpError`] through the framework integrations of
+/// this crate, as the [`as_http_json_response`](#method.as_http_json_response-1)
+/// of the error - so that handlers returning a typed error can use it as their
+/// error type.
+///
+/// ```ignore
+/// async fn lookup(Path(id): Path<String>) -> Result<Json<Item>, HttpErrorResponse<CatalogError>> {
+///     Ok(Json(catalog::find(&id)?))
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct HttpErrorResponse<E>(pub E);
+
+impl<E> From<E> for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn from(error: E) -> Self {
+        Self(error)
+    }
+}
+
+impl<E> Display for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl<E> Error for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl<E> HttpError for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn status(&self) -> StatusCode {
+        self.0.status()
+    }
+
+    fn domain(&self) -> &str {
+        self.0.domain()
+    }
+
+    fn message(&self) -> Cow<'_, str> {
+        self.0.message()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.0.headers()
+    }
+
+    fn on_render(&self) {
+        self.0.on_render();
+    }
+
+    fn text_body(&self) -> String {
+        self.0.text_body()
+    }
+
+    fn json_body(&self) -> String {
+        self.0.json_body()
+    }
+}
+
+impl dyn HttpError + '_ {
+    ///
+    /// Store the [`ErrorRecord`] of the error in `extensions` - those of the
+    /// request being handled, for example - for later middleware. Rendered
+    /// responses mostly carry it too - see [`ErrorRecord`].
+    ///
+    pub fn record_into(&self, extensions: &mut Extensions) {
+        ErrorRecord::of(self).insert_into(extensions);
+    }
+
+    ///
+    /// Return an [`http::response::Builder`] carrying the status,
+    /// [`HttpError::headers`] and [`ErrorRecord`] of the error, after calling
+    /// [`HttpError::on_render`].
+    ///
+    pub(crate) fn response_builder(&self) -> Builder {
+        self.on_render();
+        self.parts_builder()
+    }
+
+    ///
+    /// As [`response_builder`](#method.response_builder), with a `content-type`
+    /// of `application/json`.
+    ///
+    pub(crate) fn json_response_builder(&self) -> Builder {
+        self.response_builder()
+            .header(CONTENT_TYPE, "application/json")
+    }
+
+    fn parts_builder(&self) -> Builder {
+        let mut builder = Response::builder()
+            .status(self.status())
+            .extension(ErrorRecord::of(self));
+        for (name, value) in &self.headers() {
+            builder = builder.header(name, value);
+        }
+        builder
+    }
+
+    ///
+    /// Return an [`http::response::Builder`] carrying the status and headers of
+    /// the error and a `content-type` of `application/json`, to which the caller
+    /// can add headers before supplying a body - typically the
+    /// [`HttpError::json_body`].
+    ///
+    /// Unlike the response methods, this does not call [`HttpError::on_render`],
+    /// since the builder may never become a response - call it when the response
+    /// is sent.
+    ///
+    pub fn as_http_response_builder(&self) -> Builder {
+        self.parts_builder()
+            .header(CONTENT_TYPE, "application/json")
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with
+    /// a body generated from the `default` method of the generic body type.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::default()`, that isn't actually
+    /// possible.
+    pub fn as_http_response<B>(&self) -> Response<B>
+    where
+        B: Default,
+    {
+        self.response_builder()
+            .body(B::default())
+            .expect("Response::build should succeed")
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with
+    /// the [`HttpError::text_body`] and a `content-type` of `text/plain`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_string_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        self.response_builder()
+            .header(CONTENT_TYPE, "text/plain")
+            .body(self.text_body().into())
+            .expect("Response::build should succeed")
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with
+    /// the [`HttpError::json_body`] and a `content-type` of `application/json`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_json_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        self.json_response_builder()
+            .body(self.json_body().into())
+            .expect("Response::build should succeed")
+    }
+
+    ///
+    /// As [`as_http_string_response`](#method.as_http_string_response), for body
+    /// types whose construction from a `String` can fail.
+    ///
+    /// # Errors
+    /// The error of `B::try_from` if the body cannot be created.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// that isn't actually possible.
+    pub fn try_as_http_string_response<B>(&self) -> Result<Response<B>, B::Error>
+    where
+        B: TryFrom<String>,
+    {
+        let body = B::try_from(self.text_body())?;
+        Ok(self
+            .response_builder()
+            .header(CONTENT_TYPE, "text/plain")
+            .body(body)
+            .expect("Response::build should succeed"))
+    }
+
+    ///
+    /// As [`as_http_json_response`](#method.as_http_json_response), for body
+    /// types whose construction from a `String` can fail.
+    ///
+    /// # Errors
+    /// The error of `B::try_from` if the body cannot be created.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// that isn't actually possible.
+    pub fn try_as_http_json_response<B>(&self) -> Result<Response<B>, B::Error>
+    where
+        B: TryFrom<String>,
+    {
+        let body = B::try_from(self.json_body())?;
+        Ok(self
+            .json_response_builder()
+            .body(body)
+            .expect("Response::build should succeed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpError;
+    use http::{Response, StatusCode};
+    use std::borrow::Cow;
+
+    #[derive(Debug)]
+    struct NotFound(&'static str);
+
+    impl std::fmt::Display for NotFound {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "No such item: {}", self.0)
+        }
+    }
+
+    impl std::error::Error for NotFound {}
+
+    impl HttpError for NotFound {
+        fn status(&self) -> StatusCode {
+            StatusCode::NOT_FOUND
+        }
+
+        fn domain(&self) -> &str {
+            "Catalog"
+        }
+
+        fn message(&self) -> Cow<'_, str> {
+            Cow::Owned(self.to_string())
+        }
+    }
+
+    #[test]
+    fn foreign_error_responses() {
+        let error: &dyn HttpError = &NotFound("a\"b");
+
+        let response: Response<String> = error.as_http_json_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.body(),
+            r#"{"message":"No such item: a\"b","domain":"Catalog"}"#
+        );
+
+        let response: Response<String> = error.as_http_string_response();
+        assert_eq!(
+            response.body(),
+            "No such item: a\"b (application domain: Catalog)"
+        );
+
+        let error: &dyn HttpError = &crate::HttpWhatever::new("409:Orders:Duplicate order");
+        let response: Response<()> = error.as_http_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(response.headers().contains_key(crate::ERROR_ID_HEADER));
+    }
+
+    #[test]
+    fn wrapped_errors() {
+        let error = super::HttpErrorResponse::from(NotFound("a"));
+        assert_eq!(error.to_string(), "No such item: a");
+
+        let response: Response<String> = (&error as &dyn HttpError).as_http_json_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.body(),
+            r#"{"message":"No such item: a","domain":"Catalog"}"#
+        );
+    }
+
+    #[test]
+    fn fallible_bodies() {
+        #[derive(Debug)]
+        struct Limited(String);
+
+        impl TryFrom<String> for Limited {
+            type Error = usize;
+
+            fn try_from(body: String) -> Result<Self, usize> {
+                match body.len() {
+                    len if len > 45 => Err(len),
+                    _ => Ok(Limited(body)),
+                }
+            }
+        }
+
+        let error: &dyn HttpError = &NotFound("a");
+        let response = error.try_as_http_string_response::<Limited>().unwrap();
+        assert_eq!(
+            response.body().0,
+            "No such item: a (application domain: Catalog)"
+        );
+        assert_eq!(
+            error.try_as_http_json_response::<Limited>().unwrap_err(),
+            48
+        );
+    }
+}
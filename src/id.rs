@@ -0,0 +1,97 @@
+//!
+//! Per-error instance identifiers.
+//!
+use core::fmt::{self, Display};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+///
+/// An identifier unique to one error instance.
+///
+/// Generated identifiers are [ULIDs](https://github.com/ulid/spec) - 26 characters,
+/// lexicographically sortable by creation time. Identifiers received from elsewhere
+/// (a UUID from an upstream service, for example) can be used instead via the
+/// `From<String>` and `From<&str>` implementations.
+///
+/// The identifier is emitted in the `x-error-id` response header, in the JSON
+/// response body and in [`HttpWhatever::details`](crate::HttpWhatever::details),
+/// so that an incident reported by a user can be matched to the exact log entry.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorId(String);
+
+impl ErrorId {
+    ///
+    /// Generate a new ULID-based identifier.
+    ///
+    pub fn generate() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let value = (u128::from(millis & 0xFFFF_FFFF_FFFF) << 80) | random_80();
+
+        let mut id = String::with_capacity(26);
+        for i in (0..26).rev() {
+            let idx = ((value >> (i * 5)) & 0x1F) as usize;
+            id.push(CROCKFORD[idx] as char);
+        }
+        Self(id)
+    }
+
+    ///
+    /// Return the identifier as a string slice.
+    ///
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+fn random_80() -> u128 {
+    let state = RandomState::new();
+    let seq = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let mut high = state.build_hasher();
+    high.write_u64(seq);
+    let mut low = state.build_hasher();
+    low.write_u64(!seq);
+    (u128::from(high.finish() & 0xFFFF) << 64) | u128::from(low.finish())
+}
+
+impl Display for ErrorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for ErrorId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&str> for ErrorId {
+    fn from(id: &str) -> Self {
+        Self(id.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorId;
+
+    #[test]
+    fn generated_ids_are_ulids() {
+        let first = ErrorId::generate();
+        let second = ErrorId::generate();
+
+        assert_eq!(first.as_str().len(), 26);
+        assert!(first.as_str().bytes().all(|b| b.is_ascii_alphanumeric()));
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,218 @@
+//!
+//! [`actix_web`](::actix_web) responses.
+//!
+use ::actix_web::dev::ServiceResponse;
+use ::actix_web::http::StatusCode;
+use ::actix_web::middleware::{ErrorHandlerResponse, ErrorHandlers};
+use ::actix_web::{HttpMessage, HttpResponse, ResponseError};
+
+use crate::{DebugResponses, ErrorRecord, HttpError, HttpErrorResponse, HttpWhatever};
+
+///
+/// Convert `response` into an actix-web response, header by header.
+///
+fn actix_response(response: http::Response<String>) -> HttpResponse {
+    let record = response.extensions().get::<ErrorRecord>().cloned();
+    let status = StatusCode::from_u16(response.status().as_u16())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut builder = HttpResponse::build(status);
+    for (name, value) in response.headers() {
+        builder.append_header((name.as_str(), value.as_bytes().to_vec()));
+    }
+    let mut rendered = builder.body(response.into_body());
+    if let Some(record) = record {
+        rendered.extensions_mut().insert(record);
+    }
+    rendered
+}
+
+///
+/// The status of the error, from [`HttpWhatever::parts`], and the headers and
+/// body of its [`HttpWhatever::as_http_json_response`], so that handlers can
+/// return [`HttpResult<T>`](crate::HttpResult) directly.
+///
+/// actix-web uses its own HTTP types, so the response is converted header by
+/// header.
+///
+impl ResponseError for HttpWhatever {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.parts().2.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        actix_response(self.as_http_json_response())
+    }
+}
+
+///
+/// The status, headers and body of the [`as_http_json_response`](HttpError) of
+/// the wrapped error, so that handlers can return errors of their own
+/// [`HttpError`] types.
+///
+impl<E> ResponseError for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.0.status().as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        actix_response((&self.0 as &dyn HttpError).as_http_json_response())
+    }
+}
+
+///
+/// An [`ErrorHandlers`] middleware rendering every client and server error
+/// response through this crate, so that error bodies are consistent even for
+/// failures originating in other middleware or in actix-web itself.
+///
+/// A response already rendered from an `HttpWhatever` is passed through
+/// unchanged. Otherwise the response is the [`ResponseError::error_response`]
+/// of the `HttpWhatever` stored in the request extensions, if there is one, or
+/// else that of an `HttpWhatever` for the response status, domain "Http", plus
+/// the headers of the original response - `Allow` or `WWW-Authenticate`, for
+/// example. If the request extensions hold the [`DebugResponses`] marker, the
+/// `HttpWhatever` responses are those of
+/// [`HttpWhatever::as_http_json_response_for`] instead.
+///
+/// ```ignore
+/// App::new()
+///     .wrap(http_whatever::http_whatever_error_handlers())
+///     .service(lookup)
+/// ```
+///
+pub fn http_whatever_error_handlers<B: 'static>() -> ErrorHandlers<B> {
+    ErrorHandlers::new().default_handler(render_error)
+}
+
+fn render_error<B: 'static>(
+    response: ServiceResponse<B>,
+) -> ::actix_web::Result<ErrorHandlerResponse<B>> {
+    let debug = response.request().extensions().contains::<DebugResponses>();
+    let error = response
+        .response()
+        .error()
+        .and_then(|error| error.as_error::<HttpWhatever>());
+    if error.is_some() && !debug {
+        return Ok(ErrorHandlerResponse::Response(
+            response.map_into_left_body(),
+        ));
+    }
+    let extensions = response.request().extensions();
+    let render =
+        |error: &HttpWhatever| actix_response(error.as_http_json_response_debugging(debug));
+    let rendered = match error.or_else(|| extensions.get::<HttpWhatever>()) {
+        Some(error) => render(error),
+        None => {
+            let status = http::StatusCode::from_u16(response.status().as_u16())
+                .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+            let message = status.canonical_reason().unwrap_or("Unknown error");
+            let mut rendered = render(&HttpWhatever::categorized(status, "Http", message, None));
+            for (name, value) in response.headers() {
+                if !rendered.headers().contains_key(name) {
+                    rendered.headers_mut().append(name.clone(), value.clone());
+                }
+            }
+            rendered
+        }
+    };
+    drop(extensions);
+    let (request, _) = response.into_parts();
+    Ok(ErrorHandlerResponse::Response(
+        ServiceResponse::new(request, rendered).map_into_right_body(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use ::actix_web::body::MessageBody;
+    use ::actix_web::middleware::ErrorHandlerResponse;
+    use ::actix_web::test::TestRequest;
+    use ::actix_web::{HttpMessage, HttpResponse, ResponseError};
+
+    use super::render_error;
+    use crate::integrations::testing;
+    use crate::{audit, DebugResponses, ErrorRecord, HttpErrorResponse, HttpWhatever};
+
+    #[test]
+    fn error_response() {
+        let response = testing::error().error_response();
+        assert_eq!(
+            response.extensions().get::<ErrorRecord>().unwrap().domain,
+            "Catalog"
+        );
+        let status = response.status().as_u16();
+        let headers: Vec<(String, Vec<u8>)> = response
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.as_str().to_owned(), value.as_bytes().to_vec()))
+            .collect();
+        let Ok(body) = response.into_body().try_into_bytes() else {
+            panic!("the body should be complete");
+        };
+        testing::assert_response(status, headers, &body);
+    }
+
+    #[test]
+    fn wrapped_error_response() {
+        let error = HttpErrorResponse(testing::error());
+        assert_eq!(error.status_code().as_u16(), 503);
+        let response = error.error_response();
+        let status = response.status().as_u16();
+        let headers: Vec<(String, Vec<u8>)> = response
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.as_str().to_owned(), value.as_bytes().to_vec()))
+            .collect();
+        let Ok(body) = response.into_body().try_into_bytes() else {
+            panic!("the body should be complete");
+        };
+        testing::assert_response(status, headers, &body);
+    }
+
+    #[test]
+    fn one_audit_record() {
+        audit::testing::audit();
+        let error = HttpWhatever::new("403:Audited:Token revoked");
+        let id = error.id().to_string();
+        let response = TestRequest::default().to_srv_response(HttpResponse::from_error(error));
+        let Ok(ErrorHandlerResponse::Response(response)) = render_error(response) else {
+            panic!("the response should be rendered");
+        };
+        assert_eq!(response.status().as_u16(), 403);
+        assert_eq!(audit::testing::records_of(&id), 1);
+    }
+
+    #[test]
+    fn foreign_error_headers() {
+        let response = TestRequest::default().to_srv_response(
+            HttpResponse::MethodNotAllowed()
+                .insert_header(("allow", "GET"))
+                .finish(),
+        );
+        let Ok(ErrorHandlerResponse::Response(response)) = render_error(response) else {
+            panic!("the response should be rendered");
+        };
+        assert_eq!(response.status().as_u16(), 405);
+        assert_eq!(response.headers().get("allow").unwrap(), "GET");
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+    }
+
+    #[test]
+    fn debug_responses() {
+        let response = TestRequest::default().to_srv_response(HttpResponse::NotFound().finish());
+        response.request().extensions_mut().insert(DebugResponses);
+        let Ok(ErrorHandlerResponse::Response(response)) = render_error(response) else {
+            panic!("the response should be rendered");
+        };
+        assert_eq!(response.status().as_u16(), 404);
+        let Ok(body) = response.into_body().try_into_bytes() else {
+            panic!("the body should be complete");
+        };
+        assert!(String::from_utf8_lossy(&body).contains("\"details\""));
+    }
+}
@@ -0,0 +1,48 @@
+//!
+//! [`async_graphql`](::async_graphql) errors.
+//!
+use ::async_graphql::{Error, ErrorExtensions};
+
+use crate::HttpWhatever;
+
+///
+/// A GraphQL error for the error, so that resolvers can reuse the same error
+/// type as the REST endpoints. The message is the (redacted) message of the
+/// error and the `extensions` map carries its `status`, `domain` and `id`.
+///
+/// `async_graphql` converts every displayable error into an [`Error`] without
+/// extensions, so a `From<HttpWhatever>` conversion is not possible. Resolvers
+/// should use `.map_err(|e| e.extend())` - or the
+/// [`ResultExt`](::async_graphql::ResultExt) of `async_graphql` - rather than `?`
+/// alone.
+///
+impl ErrorExtensions for HttpWhatever {
+    fn extend(&self) -> Error {
+        let parts = self.parts();
+        Error::new(self.rendered_message()).extend_with(|_, extensions| {
+            extensions.set("status", parts.2.as_u16());
+            extensions.set("domain", parts.1);
+            extensions.set("id", self.id().as_str());
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::async_graphql::{ErrorExtensions, Value};
+
+    use crate::integrations::testing;
+
+    #[test]
+    fn extend() {
+        let error = testing::error().extend();
+        assert_eq!(error.message, "Catalog unavailable");
+        let extensions = error.extensions.expect("the error should have extensions");
+        assert_eq!(extensions.get("status"), Some(&Value::from(503u16)));
+        assert_eq!(extensions.get("domain"), Some(&Value::from("Catalog")));
+        assert_eq!(
+            extensions.get("id"),
+            Some(&Value::from("01ARZ3NDEKTSV4RRFFQ69G5FAV"))
+        );
+    }
+}
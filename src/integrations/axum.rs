@@ -0,0 +1,135 @@
+//!
+//! [`axum`](::axum) responses.
+//!
+use std::sync::Arc;
+
+use ::axum::body::Body;
+use ::axum::extract::Request;
+use ::axum::middleware::Next;
+use ::axum::response::{IntoResponse, Response};
+
+use crate::{DebugResponses, HttpError, HttpErrorResponse, HttpWhatever};
+
+///
+/// The error a response was rendered from, kept in the response extensions for
+/// [`http_whatever_debug_responses`].
+///
+#[derive(Clone)]
+struct RenderedError(Arc<HttpWhatever>);
+
+///
+/// The [`HttpWhatever::as_http_json_response`] of the error, so that handlers
+/// can return [`HttpResult<T>`](crate::HttpResult) directly.
+///
+/// ```ignore
+/// async fn lookup(Path(id): Path<String>) -> HttpResult<Json<Item>> {
+///     let item = catalog::find(&id).whatever_context("404:Catalog:No such item")?;
+///     Ok(Json(item))
+/// }
+/// ```
+///
+impl IntoResponse for HttpWhatever {
+    fn into_response(self) -> Response {
+        let mut response = self.as_http_json_response::<Body>();
+        response
+            .extensions_mut()
+            .insert(RenderedError(Arc::new(self)));
+        response
+    }
+}
+
+///
+/// The [`as_http_json_response`](HttpError) of the wrapped error, so that
+/// handlers can return errors of their own [`HttpError`] types.
+///
+impl<E> IntoResponse for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn into_response(self) -> Response {
+        (&self.0 as &dyn HttpError).as_http_json_response::<Body>()
+    }
+}
+
+///
+/// A middleware, for `axum::middleware::from_fn`, rendering the `HttpWhatever`
+/// responses to requests holding the [`DebugResponses`] marker as
+/// [`HttpWhatever::as_http_json_response_for`] does - with the details and full
+/// backtrace of the error.
+///
+/// The marker must be inserted by trusted middleware layered outside this one.
+///
+/// ```ignore
+/// let app = Router::new()
+///     .route("/", get(handler))
+///     .layer(middleware::from_fn(http_whatever::http_whatever_debug_responses))
+///     .layer(middleware::from_fn(authorize_debugging));
+/// ```
+///
+pub async fn http_whatever_debug_responses(request: Request, next: Next) -> Response {
+    let debug = request.extensions().get::<DebugResponses>().is_some();
+    let response = next.run(request).await;
+    if debug {
+        if let Some(RenderedError(error)) = response.extensions().get::<RenderedError>() {
+            return error.as_http_json_response_debugging(true);
+        }
+    }
+    response
+}
+
+///
+/// An error handler for `axum::error_handling::HandleErrorLayer`, rendering
+/// the errors of fallible tower middleware as
+/// [`HttpWhatever::as_http_json_response`]s: an `HttpWhatever` as is, a timeout
+/// as a `504`, a load-shedding rejection as a `503` and anything else as a `500`.
+///
+/// ```ignore
+/// let app = Router::new().route("/", get(handler)).layer(
+///     ServiceBuilder::new()
+///         .layer(HandleErrorLayer::new(http_whatever::handle_http_whatever))
+///         .timeout(Duration::from_secs(10)),
+/// );
+/// ```
+///
+#[cfg(feature = "tower")]
+pub async fn handle_http_whatever(error: ::axum::BoxError) -> Response {
+    HttpWhatever::from_box_error(error).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use ::axum::response::IntoResponse;
+
+    use crate::integrations::testing;
+    use crate::HttpErrorResponse;
+
+    #[test]
+    fn into_response() {
+        let (parts, body) = testing::error().into_response().into_parts();
+        let body = testing::block_on(::axum::body::to_bytes(body, usize::MAX)).unwrap();
+        testing::assert_response(
+            parts.status.as_u16(),
+            parts
+                .headers
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            &body,
+        );
+    }
+
+    #[test]
+    fn wrapped_into_response() {
+        let (parts, body) = HttpErrorResponse(testing::error())
+            .into_response()
+            .into_parts();
+        let body = testing::block_on(::axum::body::to_bytes(body, usize::MAX)).unwrap();
+        testing::assert_response(
+            parts.status.as_u16(),
+            parts
+                .headers
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            &body,
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//!
+//! [`gotham`](::gotham) responses.
+//!
+use ::gotham::handler::IntoResponse;
+use ::gotham::hyper::{Body, Response};
+use ::gotham::state::State;
+
+use crate::{ErrorRecord, HttpError, HttpErrorResponse, HttpWhatever};
+
+///
+/// Convert `response` into a gotham response, header by header.
+///
+fn gotham_response(response: http::Response<String>) -> Response<Body> {
+    let record = response.extensions().get::<ErrorRecord>().cloned();
+    let mut builder = Response::builder().status(response.status().as_u16());
+    for (name, value) in response.headers() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    let mut rendered = builder
+        .body(Body::from(response.into_body()))
+        .expect("Response::build should succeed");
+    if let Some(record) = record {
+        rendered.extensions_mut().insert(record);
+    }
+    rendered
+}
+
+///
+/// The [`HttpWhatever::as_http_json_response`] of the error, so that handlers
+/// can return it.
+///
+/// gotham uses its own HTTP types, so the response is converted header by header.
+///
+impl IntoResponse for HttpWhatever {
+    fn into_response(self, _: &State) -> Response<Body> {
+        gotham_response(self.as_http_json_response())
+    }
+}
+
+///
+/// The [`as_http_json_response`](HttpError) of the wrapped error, so that
+/// handlers can return errors of their own [`HttpError`] types.
+///
+impl<E> IntoResponse for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn into_response(self, _: &State) -> Response<Body> {
+        gotham_response((&self.0 as &dyn HttpError).as_http_json_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::gotham::handler::IntoResponse;
+    use ::gotham::state::State;
+
+    use crate::integrations::testing;
+
+    #[test]
+    fn into_response() {
+        let response = State::with_new(|state| testing::error().into_response(state));
+        let (parts, body) = response.into_parts();
+        let body = testing::block_on(::gotham::hyper::body::to_bytes(body)).unwrap();
+        testing::assert_response(
+            parts.status.as_u16(),
+            parts
+                .headers
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            &body,
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//!
+//! Responses for raw [`hyper`](https://docs.rs/hyper) services.
+//!
+use ::bytes::Bytes;
+use ::http_body_util::Full;
+use http::Response;
+
+use crate::HttpWhatever;
+
+impl HttpWhatever {
+    ///
+    /// Return the [`HttpWhatever::as_http_json_response`] of this error with the
+    /// `Full<Bytes>` body commonly used by hyper 1.x services.
+    ///
+    pub fn as_hyper_response(&self) -> Response<Full<Bytes>> {
+        self.as_http_json_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::http_body_util::BodyExt;
+
+    use crate::integrations::testing;
+
+    #[test]
+    fn hyper_response() {
+        let (parts, body) = testing::error().as_hyper_response().into_parts();
+        let body = testing::block_on(body.collect()).unwrap().to_bytes();
+        testing::assert_response(
+            parts.status.as_u16(),
+            parts
+                .headers
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            &body,
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//!
+//! [`lambda_http`](::lambda_http) responses.
+//!
+use ::lambda_http::{Body, Response};
+
+use crate::{HttpError, HttpErrorResponse, HttpWhatever};
+
+impl HttpWhatever {
+    ///
+    /// Return the [`HttpWhatever::as_http_json_response`] of this error as the
+    /// response of an API Gateway or Function URL handler.
+    ///
+    pub fn as_lambda_response(&self) -> Response<Body> {
+        self.as_http_json_response()
+    }
+}
+
+///
+/// The [`HttpWhatever::as_lambda_response`] of the error.
+///
+impl From<HttpWhatever> for Response<Body> {
+    fn from(error: HttpWhatever) -> Self {
+        error.as_lambda_response()
+    }
+}
+
+///
+/// The [`as_http_json_response`](HttpError) of the wrapped error.
+///
+impl<E> From<HttpErrorResponse<E>> for Response<Body>
+where
+    E: HttpError,
+{
+    fn from(error: HttpErrorResponse<E>) -> Self {
+        (&error.0 as &dyn HttpError).as_http_json_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::integrations::testing;
+
+    #[test]
+    fn lambda_response() {
+        let response = testing::error().as_lambda_response();
+        let body: &[u8] = response.body();
+        testing::assert_response(
+            response.status().as_u16(),
+            response
+                .headers()
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            body,
+        );
+    }
+}
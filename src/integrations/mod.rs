@@ -0,0 +1,118 @@
+//!
+//! Integrations rendering [`HttpWhatever`](crate::HttpWhatever)s as the
+//! responses of web frameworks and runtimes.
+//!
+#[cfg(feature = "actix-web")]
+mod actix;
+#[cfg(feature = "async-graphql")]
+mod async_graphql;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "gotham")]
+mod gotham;
+#[cfg(feature = "hyper")]
+mod hyper;
+#[cfg(feature = "lambda_http")]
+mod lambda;
+#[cfg(feature = "ntex")]
+mod ntex;
+#[cfg(feature = "rocket")]
+mod rocket;
+#[cfg(feature = "salvo")]
+mod salvo;
+#[cfg(feature = "tide")]
+mod tide;
+#[cfg(feature = "tonic")]
+mod tonic;
+#[cfg(feature = "trillium")]
+mod trillium;
+#[cfg(feature = "viz")]
+mod viz;
+#[cfg(feature = "warp")]
+mod warp;
+#[cfg(feature = "wasi")]
+mod wasi;
+#[cfg(feature = "workers")]
+mod worker;
+
+#[cfg(feature = "actix-web")]
+pub use self::actix::http_whatever_error_handlers;
+#[cfg(all(feature = "axum", feature = "tower"))]
+pub use self::axum::handle_http_whatever;
+#[cfg(feature = "axum")]
+pub use self::axum::http_whatever_debug_responses;
+#[cfg(feature = "trillium")]
+pub use self::trillium::HttpWhateverConnExt;
+#[cfg(feature = "warp")]
+pub use self::warp::recover_http_whatever;
+
+///
+/// Helpers of the integration tests, each used by some of the integrations only.
+///
+#[cfg(test)]
+#[allow(dead_code)]
+pub(crate) mod testing {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use http::header::RETRY_AFTER;
+    use http::HeaderValue;
+
+    use crate::prelude::*;
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    ///
+    /// Return the output of `future`, which must be ready when first polled - as
+    /// the futures reading the in-memory bodies of the tests are.
+    ///
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut future = std::pin::pin!(future);
+        match future.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the future should be ready"),
+        }
+    }
+
+    ///
+    /// The error rendered by the integration tests.
+    ///
+    pub(crate) fn error() -> HttpWhatever {
+        HttpWhatever::new(http_err!(503, "Catalog", "Catalog unavailable"))
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+            .with_header(RETRY_AFTER, HeaderValue::from_static("30"))
+    }
+
+    ///
+    /// Assert that the status, headers and body of a framework response are those
+    /// of the [`HttpWhatever::as_http_json_response`] of [`error`].
+    ///
+    pub(crate) fn assert_response<N, V>(
+        status: u16,
+        headers: impl IntoIterator<Item = (N, V)>,
+        body: &[u8],
+    ) where
+        N: AsRef<str>,
+        V: AsRef<[u8]>,
+    {
+        let expected = error().as_http_json_response::<String>();
+        assert_eq!(status, expected.status().as_u16());
+        let headers: Vec<(N, V)> = headers.into_iter().collect();
+        for (name, value) in expected.headers() {
+            assert!(
+                headers
+                    .iter()
+                    .any(|(n, v)| n.as_ref().eq_ignore_ascii_case(name.as_str())
+                        && v.as_ref() == value.as_bytes()),
+                "missing header {name}: {value:?}"
+            );
+        }
+        assert_eq!(body, expected.body().as_bytes());
+    }
+}
@@ -0,0 +1,83 @@
+//!
+//! [`ntex`](::ntex) responses.
+//!
+use ::ntex::http::StatusCode;
+use ::ntex::web::{DefaultError, HttpRequest, HttpResponse, WebResponseError};
+
+use crate::{HttpError, HttpErrorResponse, HttpWhatever};
+
+///
+/// Convert `response` into an ntex response, header by header.
+///
+fn ntex_response(response: http::Response<String>) -> HttpResponse {
+    let status = StatusCode::from_u16(response.status().as_u16())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut builder = HttpResponse::build(status);
+    for (name, value) in response.headers() {
+        builder.header(name.as_str(), value.as_bytes());
+    }
+    builder.body(response.into_body())
+}
+
+///
+/// The status of the error, from [`HttpWhatever::parts`], and the headers and
+/// body of its [`HttpWhatever::as_http_json_response`], so that services can
+/// return [`HttpResult<T>`](crate::HttpResult) directly.
+///
+/// ntex uses its own HTTP types, so the response is converted header by header.
+///
+impl WebResponseError<DefaultError> for HttpWhatever {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.parts().2.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self, _: &HttpRequest) -> HttpResponse {
+        ntex_response(self.as_http_json_response())
+    }
+}
+
+///
+/// The status, headers and body of the [`as_http_json_response`](HttpError) of
+/// the wrapped error, so that services can return errors of their own
+/// [`HttpError`] types.
+///
+impl<E> WebResponseError<DefaultError> for HttpErrorResponse<E>
+where
+    E: HttpError + 'static,
+{
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.0.status().as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self, _: &HttpRequest) -> HttpResponse {
+        ntex_response((&self.0 as &dyn HttpError).as_http_json_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::ntex::http::body::{Body, ResponseBody};
+    use ::ntex::web::test::TestRequest;
+    use ::ntex::web::{DefaultError, WebResponseError};
+
+    use crate::integrations::testing;
+
+    #[test]
+    fn error_response() {
+        let request = TestRequest::default().to_http_request();
+        let response =
+            WebResponseError::<DefaultError>::error_response(&testing::error(), &request);
+        let body = match response.body() {
+            ResponseBody::Body(Body::Bytes(body)) | ResponseBody::Other(Body::Bytes(body)) => body,
+            _ => panic!("the body should be complete"),
+        };
+        testing::assert_response(
+            response.status().as_u16(),
+            response
+                .headers()
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            body,
+        );
+    }
+}
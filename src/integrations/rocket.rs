@@ -0,0 +1,80 @@
+//!
+//! [`rocket`](::rocket) responses.
+//!
+use std::io::Cursor;
+
+use ::rocket::http::Status;
+use ::rocket::response::{self, Responder, Response};
+use ::rocket::Request;
+
+use crate::{HttpError, HttpErrorResponse, HttpWhatever};
+
+///
+/// Convert `response` into a rocket response, header by header.
+///
+fn rocket_response(response: http::Response<String>) -> response::Result<'static> {
+    let mut builder = Response::build();
+    builder.status(Status::new(response.status().as_u16()));
+    for (name, value) in response.headers() {
+        if let Ok(value) = value.to_str() {
+            builder.raw_header_adjoin(name.as_str().to_owned(), value.to_owned());
+        }
+    }
+    let body = response.into_body();
+    builder.sized_body(body.len(), Cursor::new(body));
+    builder.ok()
+}
+
+///
+/// The [`HttpWhatever::as_http_json_response`] of the error, with the status
+/// from [`HttpWhatever::parts`], so that routes can return
+/// [`HttpResult<T>`](crate::HttpResult) directly.
+///
+/// Rocket uses its own HTTP types, so the response is converted header by header.
+///
+impl<'r> Responder<'r, 'static> for HttpWhatever {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        rocket_response(self.as_http_json_response())
+    }
+}
+
+///
+/// The [`as_http_json_response`](HttpError) of the wrapped error, so that
+/// routes can return errors of their own [`HttpError`] types.
+///
+impl<'r, E> Responder<'r, 'static> for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        rocket_response((&self.0 as &dyn HttpError).as_http_json_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rocket::local::blocking::Client;
+
+    use crate::integrations::testing;
+    use crate::HttpResult;
+
+    #[::rocket::get("/")]
+    fn fail() -> HttpResult<&'static str> {
+        Err(testing::error())
+    }
+
+    #[test]
+    fn respond_to() {
+        let rocket = ::rocket::build().mount("/", ::rocket::routes![fail]);
+        let client = Client::tracked(rocket).expect("the rocket should launch");
+        let response = client.get("/").dispatch();
+        let status = response.status().code;
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .map(|header| (header.name().to_string(), header.value().to_owned()))
+            .collect();
+        let body = response.into_bytes().unwrap_or_default();
+        testing::assert_response(status, headers, &body);
+    }
+}
@@ -0,0 +1,75 @@
+//!
+//! [`salvo`](::salvo) responses.
+//!
+use ::salvo::writing::Scribe;
+use ::salvo::Response;
+use http::HeaderValue;
+
+use crate::{HttpError, HttpErrorResponse, HttpWhatever, ERROR_DOMAIN_HEADER};
+
+///
+/// Render the JSON response of `error` into `res`, plus its domain in an
+/// [`ERROR_DOMAIN_HEADER`].
+///
+fn render_into(error: &dyn HttpError, res: &mut Response) {
+    let (parts, body) = error.as_http_json_response::<String>().into_parts();
+    res.status_code(parts.status);
+    res.headers_mut().extend(parts.headers);
+    if let Ok(domain) = HeaderValue::from_str(error.domain()) {
+        res.headers_mut().insert(ERROR_DOMAIN_HEADER, domain);
+    }
+    res.body(body);
+}
+
+///
+/// The [`HttpWhatever::as_http_json_response`] of the error, plus its domain in
+/// an [`ERROR_DOMAIN_HEADER`], so that handlers can return
+/// [`HttpResult<T>`](crate::HttpResult) directly.
+///
+impl Scribe for HttpWhatever {
+    fn render(self, res: &mut Response) {
+        render_into(&self, res);
+    }
+}
+
+///
+/// The [`as_http_json_response`](HttpError) of the wrapped error, plus its
+/// domain in an [`ERROR_DOMAIN_HEADER`], so that handlers can return errors of
+/// their own [`HttpError`] types.
+///
+impl<E> Scribe for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn render(self, res: &mut Response) {
+        render_into(&self.0, res);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::salvo::http::ResBody;
+    use ::salvo::writing::Scribe;
+    use ::salvo::Response;
+
+    use crate::integrations::testing;
+    use crate::ERROR_DOMAIN_HEADER;
+
+    #[test]
+    fn render() {
+        let mut response = Response::new();
+        testing::error().render(&mut response);
+        let ResBody::Once(body) = &response.body else {
+            panic!("the body should be complete");
+        };
+        testing::assert_response(
+            response.status_code.map_or(0, |status| status.as_u16()),
+            response
+                .headers()
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            body,
+        );
+        assert_eq!(response.headers()[ERROR_DOMAIN_HEADER], "Catalog");
+    }
+}
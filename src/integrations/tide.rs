@@ -0,0 +1,82 @@
+//!
+//! [`tide`](::tide) errors and responses.
+//!
+use ::tide::http::headers::HeaderName;
+use ::tide::{Response, StatusCode};
+
+use crate::HttpWhatever;
+
+///
+/// The tide status for `status`, or `500` if tide does not know it.
+///
+fn tide_status(status: http::StatusCode) -> StatusCode {
+    StatusCode::try_from(status.as_u16()).unwrap_or(StatusCode::InternalServerError)
+}
+
+impl HttpWhatever {
+    ///
+    /// Convert this error into a [`tide::Error`](::tide::Error) with the status
+    /// from [`HttpWhatever::parts`], keeping the error itself - and so its
+    /// domain and message - for middleware to render, e.g. with
+    /// [`HttpWhatever::as_tide_response`].
+    ///
+    /// tide converts every error to a `tide::Error` with a `500` status, so use
+    /// this rather than `?` when the status matters.
+    ///
+    pub fn into_tide_error(self) -> ::tide::Error {
+        let status = tide_status(self.parts().2);
+        ::tide::Error::new(status, self)
+    }
+
+    ///
+    /// Return the [`HttpWhatever::as_http_json_response`] of this error as a
+    /// tide [`Response`](::tide::Response).
+    ///
+    /// ```ignore
+    /// app.with(tide::utils::After(|response: tide::Response| async move {
+    ///     Ok(match response.downcast_error::<HttpWhatever>() {
+    ///         Some(error) => error.as_tide_response(),
+    ///         None => response,
+    ///     })
+    /// }));
+    /// ```
+    ///
+    pub fn as_tide_response(&self) -> Response {
+        let (parts, body) = self.as_http_json_response::<String>().into_parts();
+        let mut response = Response::new(tide_status(parts.status));
+        for (name, value) in &parts.headers {
+            if let (Ok(name), Ok(value)) = (name.as_str().parse::<HeaderName>(), value.to_str()) {
+                response.append_header(name, value);
+            }
+        }
+        response.set_body(body);
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::integrations::testing;
+
+    #[test]
+    fn tide_response() {
+        let mut response = testing::error().as_tide_response();
+        let headers: Vec<(String, String)> = response
+            .iter()
+            .flat_map(|(name, values)| {
+                values
+                    .iter()
+                    .map(move |value| (name.as_str().to_owned(), value.as_str().to_owned()))
+            })
+            .collect();
+        let body = testing::block_on(response.take_body().into_bytes()).unwrap();
+        testing::assert_response(u16::from(response.status()), headers, &body);
+    }
+
+    #[test]
+    fn tide_error() {
+        let error = testing::error().into_tide_error();
+        assert_eq!(error.status(), ::tide::StatusCode::ServiceUnavailable);
+        assert!(error.downcast_ref::<crate::HttpWhatever>().is_some());
+    }
+}
@@ -0,0 +1,58 @@
+//!
+//! [`tonic`](::tonic) statuses.
+//!
+use ::tonic::codegen::Bytes;
+use ::tonic::metadata::{MetadataMap, MetadataValue};
+use ::tonic::{Code, Status};
+
+use crate::HttpWhatever;
+
+///
+/// A gRPC status for the error, so that services serving both REST and gRPC can
+/// use one error type:
+///
+/// * The code is the [`grpc_code`](crate::grpc_code) of the HTTP status - `404`
+///   is `NOT_FOUND`, `400` is `INVALID_ARGUMENT`, `503` is `UNAVAILABLE` and so on
+/// * The message is the (redacted) message of the error
+/// * The details are the [`HttpWhatever::grpc_status_details`]
+/// * The `x-error-domain` and `x-error-id` metadata carry the domain and
+///   [`ErrorId`](crate::ErrorId)
+///
+impl From<HttpWhatever> for Status {
+    fn from(error: HttpWhatever) -> Self {
+        let mut metadata = MetadataMap::new();
+        if let Ok(domain) = MetadataValue::try_from(error.parts().1) {
+            metadata.insert("x-error-domain", domain);
+        }
+        if let Ok(id) = MetadataValue::try_from(error.id().as_str()) {
+            metadata.insert("x-error-id", id);
+        }
+        Status::with_details_and_metadata(
+            Code::from_i32(error.grpc_code()),
+            error.rendered_message(),
+            Bytes::from(error.grpc_status_details()),
+            metadata,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::tonic::{Code, Status};
+
+    use crate::integrations::testing;
+
+    #[test]
+    fn status() {
+        let error = testing::error();
+        let details = error.grpc_status_details();
+        let status = Status::from(error);
+
+        assert_eq!(status.code(), Code::Unavailable);
+        assert_eq!(status.message(), "Catalog unavailable");
+        assert_eq!(status.details(), &details[..]);
+        let metadata = |key: &str| status.metadata().get(key).and_then(|v| v.to_str().ok());
+        assert_eq!(metadata("x-error-domain"), Some("Catalog"));
+        assert_eq!(metadata("x-error-id"), Some("01ARZ3NDEKTSV4RRFFQ69G5FAV"));
+    }
+}
@@ -0,0 +1,83 @@
+//!
+//! [`trillium`](::trillium) conns.
+//!
+use ::trillium::Conn;
+
+use crate::{HttpResult, HttpWhatever};
+
+///
+/// An extension of [`trillium::Conn`](::trillium::Conn) applying
+/// `HttpWhatever`s to the response.
+///
+/// ```ignore
+/// use http_whatever::HttpWhateverConnExt;
+///
+/// async fn lookup(conn: Conn) -> Conn {
+///     match catalog::find(conn.path()) {
+///         Ok(item) => conn.ok(item.to_json()),
+///         Err(error) => conn.with_http_whatever(&error),
+///     }
+/// }
+/// ```
+///
+pub trait HttpWhateverConnExt {
+    ///
+    /// Set the status, headers and body of the response from the
+    /// [`HttpWhatever::as_http_json_response`] of `error`, and halt the conn.
+    ///
+    fn with_http_whatever(self, error: &HttpWhatever) -> Self;
+
+    ///
+    /// Apply the error of a failed `result` as [`HttpWhateverConnExt::with_http_whatever`]
+    /// does, leaving the conn unchanged if it succeeded.
+    ///
+    fn with_http_result<T>(self, result: &HttpResult<T>) -> Self;
+}
+
+impl HttpWhateverConnExt for Conn {
+    fn with_http_whatever(self, error: &HttpWhatever) -> Self {
+        let (parts, body) = error.as_http_json_response::<String>().into_parts();
+        let mut conn = self.with_status(parts.status.as_u16());
+        for (name, value) in &parts.headers {
+            if let Ok(value) = value.to_str() {
+                conn = conn.with_response_header(name.as_str().to_owned(), value.to_owned());
+            }
+        }
+        conn.with_body(body).halt()
+    }
+
+    fn with_http_result<T>(self, result: &HttpResult<T>) -> Self {
+        match result {
+            Ok(_) => self,
+            Err(error) => self.with_http_whatever(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::trillium::Conn;
+    use ::trillium_testing::prelude::*;
+
+    use super::HttpWhateverConnExt;
+    use crate::integrations::testing;
+
+    #[test]
+    fn with_http_whatever() {
+        let mut conn =
+            get("/").on(&|conn: Conn| async move { conn.with_http_whatever(&testing::error()) });
+        let expected = testing::error().as_http_json_response::<String>();
+        let headers: Vec<(&str, String)> = expected
+            .headers()
+            .keys()
+            .filter_map(|name| {
+                let value = conn.response_headers().get_str(name.as_str())?;
+                Some((name.as_str(), value.to_owned()))
+            })
+            .collect();
+        let status = conn.status().map_or(0, |status| status as u16);
+        let body = conn.take_response_body_string().unwrap_or_default();
+        testing::assert_response(status, headers, body.as_bytes());
+        assert!(conn.is_halted());
+    }
+}
@@ -0,0 +1,51 @@
+//!
+//! [`viz`](::viz) responses.
+//!
+use ::viz::{Body, IntoResponse, Response};
+
+use crate::{HttpError, HttpErrorResponse, HttpWhatever};
+
+///
+/// The [`HttpWhatever::as_http_json_response`] of the error, so that it can be
+/// the error of viz handlers - converted with `IntoResponse::into_error`.
+///
+impl IntoResponse for HttpWhatever {
+    fn into_response(self) -> Response {
+        self.as_http_json_response::<Body>()
+    }
+}
+
+///
+/// The [`as_http_json_response`](HttpError) of the wrapped error, so that
+/// errors of other [`HttpError`] types can be the error of viz handlers.
+///
+impl<E> IntoResponse for HttpErrorResponse<E>
+where
+    E: HttpError,
+{
+    fn into_response(self) -> Response {
+        (&self.0 as &dyn HttpError).as_http_json_response::<Body>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::http_body_util::BodyExt;
+    use ::viz::IntoResponse;
+
+    use crate::integrations::testing;
+
+    #[test]
+    fn into_response() {
+        let (parts, body) = testing::error().into_response().into_parts();
+        let body = testing::block_on(body.collect()).unwrap().to_bytes();
+        testing::assert_response(
+            parts.status.as_u16(),
+            parts
+                .headers
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            &body,
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//!
+//! [`warp`](::warp) rejections and replies.
+//!
+use ::warp::hyper::Body;
+use ::warp::reject::{Reject, Rejection};
+use ::warp::reply::Response;
+
+use crate::HttpWhatever;
+
+///
+/// An `HttpWhatever` can be a custom rejection - `warp::reject::custom(error)` -
+/// turned back into a reply by [`recover_http_whatever`].
+///
+impl Reject for HttpWhatever {}
+
+///
+/// A rejection handler, for `Filter::recover`, replying to an `HttpWhatever`
+/// rejection with its [`HttpWhatever::as_http_json_response`]. Other rejections
+/// are passed on unchanged.
+///
+/// warp uses its own HTTP types, so the response is converted header by header.
+///
+/// ```ignore
+/// let routes = api.recover(http_whatever::recover_http_whatever);
+/// ```
+///
+/// # Errors
+/// The `rejection`, if it is not an `HttpWhatever`.
+///
+pub async fn recover_http_whatever(rejection: Rejection) -> Result<Response, Rejection> {
+    let Some(error) = rejection.find::<HttpWhatever>() else {
+        return Err(rejection);
+    };
+    let response = error.as_http_json_response::<String>();
+    let mut builder = ::warp::http::Response::builder().status(response.status().as_u16());
+    for (name, value) in response.headers() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    Ok(builder
+        .body(Body::from(response.into_body()))
+        .expect("Response::build should succeed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recover_http_whatever;
+    use crate::integrations::testing;
+
+    #[test]
+    fn recover() {
+        let rejection = ::warp::reject::custom(testing::error());
+        let Ok(response) = testing::block_on(recover_http_whatever(rejection)) else {
+            panic!("the rejection should be recovered");
+        };
+        let (parts, body) = response.into_parts();
+        let body = testing::block_on(::warp::hyper::body::to_bytes(body)).unwrap();
+        testing::assert_response(
+            parts.status.as_u16(),
+            parts
+                .headers
+                .iter()
+                .map(|(n, v)| (n.as_str(), v.as_bytes())),
+            &body,
+        );
+
+        let rejection = testing::block_on(recover_http_whatever(::warp::reject::not_found()));
+        assert!(rejection.is_err());
+    }
+}
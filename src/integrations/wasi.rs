@@ -0,0 +1,57 @@
+//!
+//! Responses for components targeting the WASI HTTP proxy world.
+//!
+use ::wasi::http::types::{Fields, OutgoingBody, OutgoingResponse, ResponseOutparam};
+
+use crate::HttpWhatever;
+
+///
+/// The largest write `blocking-write-and-flush` accepts.
+///
+const WRITE_CHUNK: usize = 4096;
+
+impl HttpWhatever {
+    ///
+    /// Send the [`HttpWhatever::as_http_json_response`] of this error as the
+    /// response of a `wasi:http/incoming-handler`, for components running under
+    /// `wasmtime serve`, WasmCloud and other hosts of the WASI HTTP proxy world.
+    ///
+    /// Failures to write the body are ignored - the response status and headers
+    /// have already been sent by then.
+    ///
+    /// ```ignore
+    /// impl Guest for Component {
+    ///     fn handle(request: IncomingRequest, outparam: ResponseOutparam) {
+    ///         match handle(request) {
+    ///             Ok(response) => send(response, outparam),
+    ///             Err(error) => error.send_wasi_response(outparam),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn send_wasi_response(&self, outparam: ResponseOutparam) {
+        let response = self.as_http_json_response::<String>();
+        let entries: Vec<(String, Vec<u8>)> = response
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.as_str().to_owned(), value.as_bytes().to_vec()))
+            .collect();
+        let headers = Fields::from_list(&entries).unwrap_or_else(|_| Fields::new());
+        let outgoing = OutgoingResponse::new(headers);
+        let _ = outgoing.set_status_code(response.status().as_u16());
+        let body = outgoing
+            .body()
+            .expect("the body of a new response is available");
+        ResponseOutparam::set(outparam, Ok(outgoing));
+
+        if let Ok(stream) = body.write() {
+            for chunk in response.body().as_bytes().chunks(WRITE_CHUNK) {
+                if stream.blocking_write_and_flush(chunk).is_err() {
+                    break;
+                }
+            }
+        }
+        let _ = OutgoingBody::finish(body, None);
+    }
+}
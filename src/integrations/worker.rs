@@ -0,0 +1,49 @@
+//!
+//! Cloudflare [`worker`](::worker) responses and errors.
+//!
+use ::worker::{Headers, Response};
+
+use crate::HttpWhatever;
+
+impl HttpWhatever {
+    ///
+    /// Return the [`HttpWhatever::as_http_json_response`] of this error as a
+    /// Cloudflare Workers [`Response`](::worker::Response).
+    ///
+    /// # Errors
+    /// Any failure of the Workers runtime to create the response.
+    ///
+    pub fn as_worker_response(&self) -> ::worker::Result<Response> {
+        let (parts, body) = self.as_http_json_response::<String>().into_parts();
+        let headers = Headers::new();
+        for (name, value) in &parts.headers {
+            if let Ok(value) = value.to_str() {
+                headers.append(name.as_str(), value)?;
+            }
+        }
+        Ok(Response::ok(body)?
+            .with_status(parts.status.as_u16())
+            .with_headers(headers))
+    }
+}
+
+///
+/// The [`HttpWhatever::as_worker_response`] of the error.
+///
+impl TryFrom<HttpWhatever> for Response {
+    type Error = ::worker::Error;
+
+    fn try_from(error: HttpWhatever) -> ::worker::Result<Self> {
+        error.as_worker_response()
+    }
+}
+
+///
+/// A Workers error carrying the `to_string()` output of the error - use
+/// [`HttpWhatever::as_worker_response`] to keep its status.
+///
+impl From<HttpWhatever> for ::worker::Error {
+    fn from(error: HttpWhatever) -> Self {
+        ::worker::Error::RustError(error.to_string())
+    }
+}
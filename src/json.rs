@@ -0,0 +1,115 @@
+//!
+//! Minimal JSON emission helpers used by the body renderers.
+//!
+//! The crate deliberately avoids a hard dependency on `serde_json`, so the
+//! handful of flat documents it produces are assembled here, with proper
+//! string escaping.
+//!
+use core::fmt::{Display, Write};
+
+///
+/// Append `value` to `out` as a quoted, escaped JSON string.
+///
+pub(crate) fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+///
+/// Return `value` as a quoted, escaped JSON string.
+///
+pub(crate) fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    write_string(&mut out, value);
+    out
+}
+
+///
+/// Return a JSON array from already-serialized JSON values.
+///
+pub(crate) fn array<I>(values: I) -> String
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut out = String::from("[");
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&value);
+    }
+    out.push(']');
+    out
+}
+
+///
+/// An incrementally-built flat JSON object.
+///
+#[derive(Debug)]
+pub(crate) struct JsonObject {
+    buf: String,
+}
+
+impl JsonObject {
+    pub(crate) fn new() -> Self {
+        Self {
+            buf: String::from("{"),
+        }
+    }
+
+    fn key(&mut self, key: &str) {
+        if self.buf.len() > 1 {
+            self.buf.push(',');
+        }
+        write_string(&mut self.buf, key);
+        self.buf.push(':');
+    }
+
+    ///
+    /// Add a string-valued member.
+    ///
+    pub(crate) fn string(&mut self, key: &str, value: &str) -> &mut Self {
+        self.key(key);
+        write_string(&mut self.buf, value);
+        self
+    }
+
+    ///
+    /// Add a numeric member.
+    ///
+    pub(crate) fn number(&mut self, key: &str, value: impl Display) -> &mut Self {
+        self.key(key);
+        let _ = write!(self.buf, "{value}");
+        self
+    }
+
+    ///
+    /// Add a member whose value is already-serialized JSON.
+    ///
+    pub(crate) fn raw(&mut self, key: &str, json: &str) -> &mut Self {
+        self.key(key);
+        self.buf.push_str(json);
+        self
+    }
+
+    pub(crate) fn finish(&mut self) -> String {
+        let mut buf = std::mem::take(&mut self.buf);
+        buf.push('}');
+        buf
+    }
+}
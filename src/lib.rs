@@ -45,10 +45,187 @@
 //! ```
 //!
 use core::fmt::Debug;
+use std::borrow::Cow;
 use std::error::Error;
+use std::time::Duration;
 
-use http::{header::CONTENT_TYPE, Response, StatusCode};
-use snafu::{whatever, Backtrace, Snafu};
+use http::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LANGUAGE, RETRY_AFTER, WWW_AUTHENTICATE},
+    response::Builder,
+    Response, StatusCode,
+};
+use snafu::{whatever, Backtrace, FromString, Snafu};
+
+// Lets the code generated by the derive macros name this crate in its own tests
+#[cfg(test)]
+extern crate self as http_whatever;
+
+mod audit;
+mod backtrace;
+mod batch;
+mod builder;
+#[cfg(feature = "cbor")]
+mod cbor;
+pub mod config;
+mod constructors;
+mod context;
+mod conversion;
+mod convert;
+mod date;
+mod dead_letter;
+mod debug;
+mod domain;
+mod ext;
+mod field;
+mod gateway;
+mod grpc;
+mod html;
+mod http_error;
+mod id;
+mod integrations;
+mod json;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod negotiate;
+mod odata;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+mod preset;
+mod problem;
+mod recategorize;
+mod record;
+mod redact;
+mod renderer;
+mod runtime;
+mod secrets;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "tracing")]
+mod span;
+mod xml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use audit::{AuditRecord, AUDIT_RECORD_VERSION};
+pub use batch::BatchOutcome;
+pub use builder::HttpWhateverBuilder;
+pub use config::{BacktraceStyle, JsonPreset, Mode, ResponseFormat, StatusPolicy};
+pub use context::ErrorContext;
+pub use conversion::ErrorConversion;
+pub use date::parse_retry_after;
+pub use debug::DebugResponses;
+pub use domain::Domain;
+pub use ext::{HttpResultExt, StatusExt};
+pub use field::{json_pointer, FieldError};
+pub use gateway::{GatewayMap, UPSTREAM_BODY_LIMIT};
+pub use grpc::{grpc_code, GRPC_MESSAGE, GRPC_STATUS, GRPC_STATUS_DETAILS_BIN};
+pub use http_error::{HttpError, HttpErrorResponse};
+#[cfg(feature = "derive")]
+pub use http_whatever_derive::HttpDomain;
+pub use id::ErrorId;
+#[cfg(all(feature = "axum", feature = "tower"))]
+pub use integrations::handle_http_whatever;
+#[cfg(feature = "axum")]
+pub use integrations::http_whatever_debug_responses;
+#[cfg(feature = "actix-web")]
+pub use integrations::http_whatever_error_handlers;
+#[cfg(feature = "warp")]
+pub use integrations::recover_http_whatever;
+#[cfg(feature = "trillium")]
+pub use integrations::HttpWhateverConnExt;
+pub use record::ErrorRecord;
+pub use redact::{Redactor, StandardRedactor};
+pub use renderer::{BodyRenderer, ErrorParts};
+pub use runtime::RuntimeContext;
+pub use secrets::{find_secret, SecretKind, SecretScan};
+#[cfg(feature = "tracing")]
+pub use span::SpanFieldsLayer;
+
+///
+/// The response header carrying the [`ErrorId`] of the error.
+///
+pub const ERROR_ID_HEADER: HeaderName = HeaderName::from_static("x-error-id");
+
+///
+/// The response header carrying the domain of the error, for integrations
+/// that emit it.
+///
+pub const ERROR_DOMAIN_HEADER: HeaderName = HeaderName::from_static("x-error-domain");
+
+///
+/// The upstream response headers worth preserving on an error response by
+/// default - see [`HttpWhatever::with_upstream_headers`].
+///
+pub const DEFAULT_UPSTREAM_HEADERS: [HeaderName; 8] = [
+    RETRY_AFTER,
+    WWW_AUTHENTICATE,
+    HeaderName::from_static("ratelimit-limit"),
+    HeaderName::from_static("ratelimit-remaining"),
+    HeaderName::from_static("ratelimit-reset"),
+    HeaderName::from_static("x-ratelimit-limit"),
+    HeaderName::from_static("x-ratelimit-remaining"),
+    HeaderName::from_static("x-ratelimit-reset"),
+];
+
+///
+/// The RFC 7240 `Preference-Applied` response header.
+///
+pub const PREFERENCE_APPLIED: HeaderName = HeaderName::from_static("preference-applied");
+
+///
+/// The trailer carrying the HTTP status of a mid-stream failure.
+///
+pub const STREAM_ERROR_STATUS_TRAILER: HeaderName =
+    HeaderName::from_static("x-stream-error-status");
+
+///
+/// The trailer carrying the domain of a mid-stream failure.
+///
+pub const STREAM_ERROR_DOMAIN_TRAILER: HeaderName =
+    HeaderName::from_static("x-stream-error-domain");
+
+///
+/// The trailer carrying the (percent-encoded) message of a mid-stream failure.
+///
+pub const STREAM_ERROR_MESSAGE_TRAILER: HeaderName =
+    HeaderName::from_static("x-stream-error-message");
+
+#[doc(hidden)]
+pub mod __private {
+    pub use http::StatusCode;
+
+    ///
+    /// Whether the source text of a literal status given to `http_err!` is a
+    /// status between 100 and 999 - any literal other than a decimal integer,
+    /// such as a string, is left unchecked.
+    ///
+    pub const fn is_valid_status_literal(literal: &str) -> bool {
+        let bytes = literal.as_bytes();
+        if bytes.is_empty() || bytes[0] == b'-' {
+            return false;
+        }
+        if !bytes[0].is_ascii_digit()
+            || (bytes.len() > 1 && bytes[0] == b'0' && matches!(bytes[1], b'x' | b'o' | b'b'))
+        {
+            return true;
+        }
+        let mut status = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            if b.is_ascii_digit() {
+                status = status * 10 + (b - b'0') as u32;
+                if status > 999 {
+                    return false;
+                }
+            } else if b != b'_' {
+                break;
+            }
+            i += 1;
+        }
+        100 <= status
+    }
+}
 
 pub type HttpResult<A> = std::result::Result<A, HttpWhatever>;
 
@@ -58,8 +235,24 @@ pub type HttpResult<A> = std::result::Result<A, HttpWhatever>;
 ///
 /// `http_err!(status<default 500>,domain<default "unknown">,msg)`
 ///
+/// A status given as an integer literal must be between 100 and 999, which is
+/// checked at compile time:
+///
+/// ```compile_fail
+/// use http_whatever::http_err;
+///
+/// let message = http_err!(6000, "Api", "Bad status");
+/// ```
+///
 #[macro_export]
 macro_rules! http_err {
+    ($s:literal,$d:expr,$e:expr) => {{
+        const _: () = assert!(
+            $crate::__private::is_valid_status_literal(stringify!($s)),
+            "the status of http_err! must be between 100 and 999"
+        );
+        format!("{}:{}:{}", $s, $d, $e)
+    }};
     ($s:expr,$d:expr,$e:expr) => {
         format!("{}:{}:{}", $s, $d, $e)
     };
@@ -71,6 +264,176 @@ macro_rules! http_err {
     };
 }
 
+///
+/// A macro to test the category of an `HttpWhatever` tersely - in middleware or
+/// tests, for example.
+///
+/// `http_matches!(err, status, domain)` or `http_matches!(err, status)`, where
+/// the status can be a number or a [`http::StatusCode`], and either the status or
+/// the domain can be `_` to match anything.
+///
+/// ```
+/// use http_whatever::prelude::*;
+///
+/// let err = HttpWhatever::new(http_err!(404, "Catalog", "No such item"));
+/// assert!(http_matches!(err, 404, "Catalog"));
+/// assert!(http_matches!(err, _, "Catalog"));
+/// assert!(http_matches!(err, http::StatusCode::NOT_FOUND));
+/// assert!(!http_matches!(err, 404, "Orders"));
+/// ```
+///
+#[macro_export]
+macro_rules! http_matches {
+    ($e:expr, _, _) => {{
+        let _ = &$e;
+        true
+    }};
+    ($e:expr, _, $d:expr) => {
+        $e.parts().1 == $d
+    };
+    ($e:expr, $s:expr, _) => {
+        $e.parts().2 == $s
+    };
+    ($e:expr, $s:expr, $d:expr) => {{
+        let parts = $e.parts();
+        parts.2 == $s && parts.1 == $d
+    }};
+    ($e:expr, $s:expr) => {
+        $e.parts().2 == $s
+    };
+}
+
+///
+/// A macro to check the status of a response received from an upstream
+/// dependency, returning early with an [`HttpWhatever::unexpected_status`] error
+/// if it is not the expected status.
+///
+/// `ensure_status!(response, expected, domain)` or, to also record the start of
+/// the response body, `ensure_status!(response, expected, domain, body)`. The
+/// response can be anything with a `status()` method returning a
+/// [`http::StatusCode`], the domain any [`Domain`] and the body anything that is
+/// `AsRef<[u8]>`.
+///
+/// ```
+/// use http::{Response, StatusCode};
+/// use http_whatever::prelude::*;
+///
+/// fn check(response: &Response<String>) -> HttpResult<()> {
+///     ensure_status!(response, StatusCode::OK, "Inventory", response.body());
+///     Ok(())
+/// }
+///
+/// let mut response = Response::new("down for maintenance".to_string());
+/// *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+/// let error = check(&response).unwrap_err();
+/// assert!(http_matches!(error, 502, "Inventory"));
+/// ```
+///
+#[macro_export]
+macro_rules! ensure_status {
+    ($r:expr, $s:expr, $d:expr $(,)?) => {{
+        let received = $r.status();
+        if received != $s {
+            return Err($crate::HttpWhatever::unexpected_status(received, $s, $d).into());
+        }
+    }};
+    ($r:expr, $s:expr, $d:expr, $b:expr $(,)?) => {{
+        let received = $r.status();
+        if received != $s {
+            return Err($crate::HttpWhatever::unexpected_status(received, $s, $d)
+                .with_body_snippet($b)
+                .into());
+        }
+    }};
+}
+
+///
+/// A macro to create an `HttpWhatever` of the given status and domain, with a
+/// message formatted from the remaining arguments, recording where it was
+/// created as its [`HttpWhatever::location`].
+///
+/// `http_whatever!(status, domain, fmt, args...)`, where the status can be a
+/// number or a [`http::StatusCode`] - an invalid status is `500` - and the
+/// domain is any [`Domain`].
+///
+/// ```
+/// use http_whatever::prelude::*;
+///
+/// let id = 7;
+/// let error = http_whatever!(404, "db", "no row for id {}", id);
+/// assert!(http_matches!(error, 404, "db"));
+/// assert_eq!(error.parts().0, "no row for id 7");
+/// assert_eq!(error.location().unwrap().file(), file!());
+/// ```
+///
+#[macro_export]
+macro_rules! http_whatever {
+    ($s:expr, $d:expr, $($arg:tt)+) => {
+        $crate::HttpWhatever::from_macro($s, $d, format!($($arg)+))
+    };
+}
+
+///
+/// A macro to return early with an `HttpWhatever` of the given status and
+/// domain, and a message formatted from the remaining arguments - as
+/// [`snafu::whatever!`] does, but with the status and domain as arguments rather
+/// than embedded in the format string.
+///
+/// `http_bail!(status, domain, fmt, args...)`, where the status can be a number
+/// or a [`http::StatusCode`] - an invalid status is `500` - and the domain is any
+/// [`Domain`].
+///
+/// ```
+/// use http_whatever::prelude::*;
+///
+/// fn lookup(id: u32) -> HttpResult<String> {
+///     if id == 0 {
+///         http_bail!(404, "Catalog", "No item {id}");
+///     }
+///     Ok(format!("item {id}"))
+/// }
+///
+/// let error = lookup(0).unwrap_err();
+/// assert!(http_matches!(error, 404, "Catalog"));
+/// assert_eq!(error.parts().0, "No item 0");
+/// ```
+///
+#[macro_export]
+macro_rules! http_bail {
+    ($s:expr, $d:expr, $($arg:tt)+) => {
+        return Err($crate::HttpWhatever::from_macro($s, $d, format!($($arg)+)).into())
+    };
+}
+
+///
+/// A macro to return early with an `HttpWhatever`, as [`http_bail!`] does, if
+/// a condition is false - as [`snafu::ensure_whatever!`] does, but with the
+/// status and domain as arguments.
+///
+/// `http_ensure!(condition, status, domain, fmt, args...)`
+///
+/// ```
+/// use http_whatever::prelude::*;
+///
+/// fn reserve(quantity: u32, stock: u32) -> HttpResult<u32> {
+///     http_ensure!(quantity <= stock, 409, "Inventory", "Only {stock} left");
+///     Ok(stock - quantity)
+/// }
+///
+/// assert_eq!(reserve(2, 5).unwrap(), 3);
+/// let error = reserve(7, 5).unwrap_err();
+/// assert!(http_matches!(error, 409, "Inventory"));
+/// ```
+///
+#[macro_export]
+macro_rules! http_ensure {
+    ($c:expr, $s:expr, $d:expr, $($arg:tt)+) => {
+        if !$c {
+            $crate::http_bail!($s, $d, $($arg)+);
+        }
+    };
+}
+
 ///
 /// An almost-drop-in replacement for [`snafu::Whatever`] with the following benefits:
 ///
@@ -80,6 +443,7 @@ macro_rules! http_err {
 ///   the response
 /// * A public `new` constructor that facilitates better ergonomics in certain error situations.
 /// * A public `parts` method to retrieve the three parts of the error.
+/// * A unique [`ErrorId`] per instance, for correlating responses with logs.
 ///
 /// Otherwise it is exactly the same as [`snafu::Whatever`] and can be used in exactly the same
 /// way.
@@ -95,6 +459,8 @@ pub struct HttpWhatever {
     source: Option<Box<dyn std::error::Error + Send + Sync>>,
     message: String,
     backtrace: Backtrace,
+    #[snafu(implicit)]
+    context: Box<ErrorContext>,
 }
 
 impl HttpWhatever {
@@ -111,8 +477,18 @@ impl HttpWhatever {
     /// from the error, but still want the categorization that this error type
     /// allows.
     ///
+    /// Note that the message is returned as-is - the configured [`Redactor`]
+    /// is only applied when the error is rendered.
+    ///
+    /// When the message carries no explicit status, the status is determined by
+    /// the configured [`StatusPolicy`].
+    ///
     pub fn parts(&self) -> (&str, &str, StatusCode) {
-        let parts: Vec<&str> = self.message.splitn(3, ':').collect::<Vec<&str>>();
+        self.parts_with_policy(config::current().status_policy)
+    }
+
+    fn parts_with_policy(&self, policy: StatusPolicy) -> (&str, &str, StatusCode) {
+        let parts: Vec<&str> = self.raw_message().splitn(3, ':').collect::<Vec<&str>>();
         let mut idx = parts.len();
 
         let message = if idx == 0 {
@@ -128,36 +504,395 @@ impl HttpWhatever {
             parts[idx]
         };
         let status_code = if idx == 0 {
-            StatusCode::INTERNAL_SERVER_ERROR
+            None
         } else {
-            StatusCode::from_bytes(parts[idx - 1].as_bytes())
-                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+            Some(
+                StatusCode::from_bytes(parts[idx - 1].as_bytes())
+                    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            )
         };
+        let status_code = match policy {
+            StatusPolicy::Outermost => status_code,
+            StatusPolicy::InnermostExplicit => status_code
+                .filter(|status| *status != StatusCode::INTERNAL_SERVER_ERROR)
+                .or_else(|| self.innermost_explicit_status()),
+        }
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
 
         (message, domain, status_code)
     }
 
+    ///
+    /// Return `true` if the error has the given status and domain.
+    ///
+    /// See also the [`http_matches!`] macro, which allows either to be omitted.
+    ///
+    pub fn is(&self, status: StatusCode, domain: &str) -> bool {
+        let parts = self.parts();
+        parts.2 == status && parts.1 == domain
+    }
+
+    ///
+    /// Return the first error in the chain - this error followed by its nested
+    /// sources - for which `predicate` returns `true`.
+    ///
+    pub fn find_in_chain<F>(&self, mut predicate: F) -> Option<&(dyn Error + 'static)>
+    where
+        F: FnMut(&(dyn Error + 'static)) -> bool,
+    {
+        let mut current: Option<&(dyn Error + 'static)> = Some(self);
+        while let Some(e) = current {
+            if predicate(e) {
+                return Some(e);
+            }
+            current = e.source();
+        }
+        None
+    }
+
+    ///
+    /// Return `true` if this error, or any `HttpWhatever` among its nested sources,
+    /// has the given domain.
+    ///
+    pub fn has_domain(&self, domain: &str) -> bool {
+        self.find_in_chain(|e| {
+            e.downcast_ref::<HttpWhatever>()
+                .is_some_and(|e| e.parts().1 == domain)
+        })
+        .is_some()
+    }
+
+    ///
+    /// Return `true` if this error, or any of its nested sources, is of type `E`.
+    ///
+    pub fn chain_contains<E>(&self) -> bool
+    where
+        E: Error + 'static,
+    {
+        self.find_in_chain(|e| e.is::<E>()).is_some()
+    }
+
+    ///
+    /// The status given explicitly in the message of this error, if any.
+    ///
+    fn explicit_status(&self) -> Option<StatusCode> {
+        let mut parts = self.raw_message().splitn(3, ':');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(status), Some(_), Some(_)) => Some(
+                StatusCode::from_bytes(status.as_bytes())
+                    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            ),
+            _ => None,
+        }
+    }
+
+    ///
+    /// The explicit status of the innermost `HttpWhatever` in the source chain
+    /// that has one other than `500`.
+    ///
+    fn innermost_explicit_status(&self) -> Option<StatusCode> {
+        let mut status = None;
+        let mut source = self.source();
+        while let Some(e) = source {
+            if let Some(inner) = e.downcast_ref::<HttpWhatever>() {
+                status = inner
+                    .explicit_status()
+                    .filter(|status| *status != StatusCode::INTERNAL_SERVER_ERROR)
+                    .or(status);
+            }
+            source = e.source();
+        }
+        status
+    }
+
+    ///
+    /// The full, structured message string, formatting it first if it was
+    /// provided lazily.
+    ///
+    fn raw_message(&self) -> &str {
+        self.context.lazy_message().unwrap_or(&self.message)
+    }
+
+    ///
+    /// Return the unique identifier of this error instance.
+    ///
+    pub fn id(&self) -> &ErrorId {
+        self.context.id()
+    }
+
+    ///
+    /// Return the implicit context captured with this error.
+    ///
+    pub fn context(&self) -> &ErrorContext {
+        &self.context
+    }
+
+    ///
+    /// Return the key/value metadata attached to this error.
+    ///
+    pub fn metadata(&self) -> &std::collections::BTreeMap<String, String> {
+        self.context.metadata()
+    }
+
+    ///
+    /// Attach an item of key/value metadata to this error.
+    ///
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.context.insert_metadata(key, value);
+        self
+    }
+
+    ///
+    /// Return how long the client should wait before retrying, if known.
+    ///
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.context.retry_after()
+    }
+
+    ///
+    /// Set how long the client should wait before retrying. The duration is
+    /// emitted, rounded up to whole seconds, in the `Retry-After` header of
+    /// the HTTP responses.
+    ///
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.context.set_retry_after(retry_after);
+        self
+    }
+
+    ///
+    /// Return the additional headers emitted on the HTTP responses generated
+    /// from this error.
+    ///
+    pub fn response_headers(&self) -> &HeaderMap {
+        self.context.headers()
+    }
+
+    ///
+    /// Add a header to the HTTP responses generated from this error.
+    ///
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.context.headers_mut().append(name, value);
+        self
+    }
+
+    ///
+    /// Capture the headers named in `allow` from the `upstream` response headers,
+    /// to be re-emitted on the HTTP responses generated from this error.
+    ///
+    /// This is intended for errors built from an upstream response, so that
+    /// headers such as `Retry-After`, `WWW-Authenticate` and the rate-limit
+    /// headers reach the downstream client. [`DEFAULT_UPSTREAM_HEADERS`] is
+    /// a sensible allow-list.
+    ///
+    /// An allowed `Retry-After` header (in seconds or as an HTTP-date) is parsed
+    /// and set as the retry-after duration of this error, so that it is re-emitted
+    /// downstream as a number of seconds.
+    ///
+    pub fn with_upstream_headers(mut self, upstream: &HeaderMap, allow: &[HeaderName]) -> Self {
+        for name in allow {
+            if name == RETRY_AFTER {
+                if let Some(retry_after) = upstream.get(RETRY_AFTER).and_then(parse_retry_after) {
+                    self.context.set_retry_after(retry_after);
+                }
+                continue;
+            }
+            for value in upstream.get_all(name) {
+                self.context
+                    .headers_mut()
+                    .append(name.clone(), value.clone());
+            }
+        }
+        self
+    }
+
+    ///
+    /// Return the language tag of the message, if it was localized.
+    ///
+    pub fn locale(&self) -> Option<&str> {
+        self.context.locale()
+    }
+
+    ///
+    /// Record that the message is in the language identified by the `locale` tag
+    /// (`en`, `fr-CA`...) - typically by whatever translated it.
+    ///
+    /// The HTTP responses generated from the error then carry a `Content-Language`
+    /// header, and the JSON body a `locale` member, so that clients and caches
+    /// know which language they received.
+    ///
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.context.set_locale(locale);
+        self
+    }
+
+    ///
+    /// Return the field-level validation failures behind this error.
+    ///
+    pub fn field_errors(&self) -> &[FieldError] {
+        self.context.field_errors()
+    }
+
+    ///
+    /// Add a field-level validation failure to this error. Field errors are
+    /// rendered in the JSON body as an `errors` array of
+    /// `{"pointer":"<JSON pointer>","code":"<code>","message":"<message>"}`.
+    ///
+    pub fn with_field_error(mut self, error: FieldError) -> Self {
+        self.context.push_field_error(error);
+        self
+    }
+
+    ///
+    /// Attach `source` as the underlying cause of this error, replacing any
+    /// source it already had - so that the categorized error can be created
+    /// first and its cause added afterwards.
+    ///
+    /// ```rust
+    /// use std::num::ParseIntError;
+    /// use http_whatever::HttpWhatever;
+    ///
+    /// let error = HttpWhatever::new("400:Input:Bad count")
+    ///     .with_source("x".parse::<u8>().unwrap_err());
+    ///
+    /// assert!(error.chain_contains::<ParseIntError>());
+    /// ```
+    ///
+    /// (This inherent method shadows `snafu::FromString::with_source`, which
+    /// remains available as `<HttpWhatever as FromString>::with_source`.)
+    ///
+    pub fn with_source(mut self, source: impl Error + Send + Sync + 'static) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    ///
+    /// Replace the generated identifier of this error with one received from
+    /// elsewhere - a request or correlation ID, for example.
+    ///
+    pub fn with_id(mut self, id: impl Into<ErrorId>) -> Self {
+        self.context.set_id(id.into());
+        self
+    }
+
+    ///
+    /// The message as it should be rendered - scanned for secrets and redacted
+    /// according to the current [`config`].
+    ///
+    fn rendered_message(&self) -> Cow<'_, str> {
+        render_text(self.id(), self.parts().0)
+    }
+
     fn display(&self) -> String {
         let parts = self.parts();
         format!(
             "{}: (Domain: {}, HTTP status: {})",
-            parts.0, parts.1, parts.2
+            self.rendered_message(),
+            parts.1,
+            parts.2
         )
     }
 
     ///
-    /// Return a String that provides the `to_string()` output of this error and all nested sources.
+    /// Return a String that provides the `to_string()` output of this error, its
+    /// [`ErrorId`], its [`HttpWhatever::location`] and [`RuntimeContext`] (if
+    /// known), and the `to_string()` output of all nested sources.
     ///
     pub fn details(&self) -> String {
-        let mut s = self.to_string();
+        let mut s = format!("{} [error id: {}]", self, self.id());
+        if let Some(location) = self.location() {
+            s.push_str(&format!(" [at {location}]"));
+        }
+        if let Some(runtime) = self.context.runtime() {
+            s.push_str(&format!(" [{runtime}]"));
+        }
         let mut source = self.source();
         while let Some(e) = source {
-            s.push_str(&format!("\n[{e}]"));
+            s.push_str(&format!("\n[{}]", render_text(self.id(), &e.to_string())));
             source = e.source();
         }
         s
     }
 
+    ///
+    /// Return a report of the error for logs and consoles: its
+    /// [`HttpWhatever::details`] followed, unless the
+    /// [`BacktraceStyle`] of the current [`config`] is `Off`, by its backtrace.
+    ///
+    pub fn report(&self) -> String {
+        self.report_with(config::current().backtrace_style)
+    }
+
+    fn report_with(&self, style: BacktraceStyle) -> String {
+        let mut report = self.details();
+        if let Some(backtrace) = self.rendered_backtrace_with(style) {
+            report.push_str("\n\nBacktrace:\n");
+            report.push_str(&backtrace);
+        }
+        report
+    }
+
+    ///
+    /// The headers common to every response rendered for this error.
+    ///
+    fn error_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Ok(id) = HeaderValue::from_str(self.id().as_str()) {
+            headers.append(ERROR_ID_HEADER, id);
+        }
+        if let Some(retry_after) = self.retry_after() {
+            let seconds = retry_after
+                .as_secs()
+                .saturating_add(u64::from(retry_after.subsec_nanos() > 0));
+            headers.append(RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        if let Some(Ok(locale)) = self.locale().map(HeaderValue::from_str) {
+            headers.append(CONTENT_LANGUAGE, locale);
+        }
+        for (name, value) in self.response_headers() {
+            if name != RETRY_AFTER || self.retry_after().is_none() {
+                headers.append(name, value.clone());
+            }
+        }
+        headers
+    }
+
+    ///
+    /// Store the [`ErrorRecord`] of this error in `extensions` - those of the
+    /// request being handled, for example - so that later middleware can act on
+    /// its categorization. Rendered responses mostly carry it too - see
+    /// [`ErrorRecord`].
+    ///
+    pub fn record_into(&self, extensions: &mut http::Extensions) {
+        (self as &dyn HttpError).record_into(extensions);
+    }
+
+    ///
+    /// Return an [`http::response::Builder`] already carrying the status, the
+    /// headers of [`HttpWhatever::as_http_json_response`] and a `content-type` of
+    /// `application/json`, so that the caller can add headers or supply a
+    /// different body before finishing the response.
+    ///
+    /// The builder does not emit the audit record of the error - see
+    /// [`HttpError::on_render`].
+    ///
+    /// ```rust
+    /// use http::header::CACHE_CONTROL;
+    /// use http_whatever::{HttpError, HttpWhatever};
+    ///
+    /// let error = HttpWhatever::new("404:Catalog:No such item");
+    /// let response = error
+    ///     .as_http_response_builder()
+    ///     .header(CACHE_CONTROL, "no-store")
+    ///     .body(error.json_body())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.status(), 404);
+    /// ```
+    ///
+    pub fn as_http_response_builder(&self) -> Builder {
+        (self as &dyn HttpError).as_http_response_builder()
+    }
+
     ///
     /// Return an [`http::Response<B>`] representation of the error, with
     /// a body generated from the `default` method of the generic body type.
@@ -171,11 +906,7 @@ impl HttpWhatever {
     where
         B: Default,
     {
-        let parts = self.parts();
-        Response::builder()
-            .status(parts.2)
-            .body(B::default())
-            .expect("Response::build should succeed")
+        (self as &dyn HttpError).as_http_response()
     }
 
     ///
@@ -198,14 +929,7 @@ impl HttpWhatever {
     where
         B: From<String>,
     {
-        let parts = self.parts();
-        let body_str = format!("{} (application domain: {})", parts.0, parts.1);
-        let body: B = body_str.into();
-        Response::builder()
-            .status(parts.2)
-            .header(CONTENT_TYPE, "text/plain")
-            .body(body)
-            .expect("Response::build should succeed")
+        (self as &dyn HttpError).as_http_string_response()
     }
 
     ///
@@ -214,7 +938,14 @@ impl HttpWhatever {
     ///
     /// The string in the response body will be of the format
     ///
-    /// `{"message":"<message>","domain":"<domain>"}`
+    /// `{"message":"<message>","domain":"<domain>","id":"<id>"}`
+    ///
+    /// with the numeric status added as a `status` member, after the domain, if
+    /// [`Config::json_status`](config::Config::json_status) is set.
+    ///
+    /// The locale of a localized message is added as a `locale` member, any field
+    /// errors as an `errors` member and, with the `serde` feature, any data
+    /// attached with `with_data` as a `data` member.
     ///
     /// The `content-type` header of the response will be `application/json`.
     ///
@@ -227,38 +958,481 @@ impl HttpWhatever {
     where
         B: From<String>,
     {
-        let parts = self.parts();
-        let body_str = format!("{{\"message\":\"{}\",\"domain\":\"{}\"}}", parts.0, parts.1);
-        let body: B = body_str.into();
-        Response::builder()
-            .status(parts.2)
-            .header(CONTENT_TYPE, "application/json")
-            .body(body)
-            .expect("Response::build should succeed")
+        (self as &dyn HttpError).as_http_json_response()
     }
 
     ///
-    /// Create a new `HttpWhatever` from the input string.
+    /// As [`HttpWhatever::as_http_string_response`], for body types whose
+    /// construction from a `String` can fail - size-limited or validated bodies,
+    /// for example.
     ///
-    /// The input string should conform to the structure documented in the
-    /// crate documentation.
+    /// # Errors
+    /// The error of `B::try_from` if the body cannot be created.
     ///
-    pub fn new(message: impl std::fmt::Display) -> Self {
-        let err_gen = |message| -> HttpResult<()> { whatever!("{message}") };
-        err_gen(message).unwrap_err()
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// that isn't actually possible.
+    pub fn try_as_http_string_response<B>(&self) -> Result<Response<B>, B::Error>
+    where
+        B: TryFrom<String>,
+    {
+        (self as &dyn HttpError).try_as_http_string_response()
     }
-}
 
-///
-///  A prelude of the main items required to use this type effectively.
-///
+    ///
+    /// As [`HttpWhatever::as_http_json_response`], for body types whose
+    /// construction from a `String` can fail.
+    ///
+    /// # Errors
+    /// The error of `B::try_from` if the body cannot be created.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// that isn't actually possible.
+    pub fn try_as_http_json_response<B>(&self) -> Result<Response<B>, B::Error>
+    where
+        B: TryFrom<String>,
+    {
+        (self as &dyn HttpError).try_as_http_json_response()
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error in the
+    /// [`ResponseFormat`] of the current [`config`] - which can be set with the
+    /// `HTTP_WHATEVER_FORMAT` environment variable.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// that isn't actually possible.
+    pub fn as_http_default_response<B>(&self) -> Response<B>
+    where
+        B: Default + From<String>,
+    {
+        self.as_http_formatted_response(config::current().format)
+    }
+
+    fn as_http_formatted_response<B>(&self, format: ResponseFormat) -> Response<B>
+    where
+        B: Default + From<String>,
+    {
+        match format {
+            ResponseFormat::Empty => self.as_http_response(),
+            ResponseFormat::Text => self.as_http_string_response(),
+            ResponseFormat::Json => self.as_http_json_response(),
+        }
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error that honors
+    /// the RFC 7240 `Prefer` request header.
+    ///
+    /// When the client asked for `return=minimal`, the response has no body and
+    /// carries a `Preference-Applied: return=minimal` header, as for high-volume
+    /// machine clients that only need the status. Otherwise it is the response
+    /// of [`HttpWhatever::as_http_json_response`].
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// that isn't actually possible.
+    pub fn as_http_response_preferring<B>(&self, prefer: Option<&HeaderValue>) -> Response<B>
+    where
+        B: Default + From<String>,
+    {
+        if prefers_minimal(prefer) {
+            let mut response = self.as_http_response();
+            response.headers_mut().insert(
+                PREFERENCE_APPLIED,
+                HeaderValue::from_static("return=minimal"),
+            );
+            response
+        } else {
+            self.as_http_json_response()
+        }
+    }
+
+    ///
+    /// Return a set of HTTP trailers describing the error, for streaming responses
+    /// whose status line and headers have already been sent.
+    ///
+    /// The trailers are
+    ///
+    /// * `x-stream-error-status`: the HTTP status code, e.g. `503`
+    /// * `x-stream-error-domain`: the domain
+    /// * `x-stream-error-message`: the message, percent-encoded in the same way as
+    ///   the gRPC `grpc-message` trailer
+    /// * `x-error-id`: the [`ErrorId`]
+    ///
+    pub fn as_trailers(&self) -> HeaderMap {
+        let parts = self.parts();
+        let mut trailers = HeaderMap::new();
+        trailers.insert(
+            STREAM_ERROR_STATUS_TRAILER,
+            HeaderValue::from(parts.2.as_u16()),
+        );
+        if let Ok(domain) = HeaderValue::from_str(&percent_encode(parts.1)) {
+            trailers.insert(STREAM_ERROR_DOMAIN_TRAILER, domain);
+        }
+        if let Ok(message) = HeaderValue::from_str(&percent_encode(&self.rendered_message())) {
+            trailers.insert(STREAM_ERROR_MESSAGE_TRAILER, message);
+        }
+        if let Ok(id) = HeaderValue::from_str(self.id().as_str()) {
+            trailers.insert(ERROR_ID_HEADER, id);
+        }
+        trailers
+    }
+
+    ///
+    /// Create a new `HttpWhatever` from the input string.
+    ///
+    /// The input string should conform to the structure documented in the
+    /// crate documentation.
+    ///
+    pub fn new(message: impl std::fmt::Display) -> Self {
+        let err_gen = |message| -> HttpResult<()> { whatever!("{message}") };
+        err_gen(message).unwrap_err()
+    }
+
+    ///
+    /// Create a new `HttpWhatever` from the input string, wrapping `source` as
+    /// its underlying cause.
+    ///
+    /// The input string should conform to the structure documented in the
+    /// crate documentation.
+    ///
+    /// ```rust
+    /// use std::num::ParseIntError;
+    /// use http_whatever::HttpWhatever;
+    ///
+    /// let cause = "x".parse::<u8>().unwrap_err();
+    /// let error = HttpWhatever::new_with_source("400:Input:Bad count", cause);
+    ///
+    /// assert!(error.chain_contains::<ParseIntError>());
+    /// ```
+    ///
+    pub fn new_with_source(
+        message: impl std::fmt::Display,
+        source: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        <Self as FromString>::with_source(source.into(), message.to_string())
+    }
+
+    ///
+    /// Create a new `HttpWhatever` for the macros of this crate, with a status
+    /// that may be given as a number, recording the location of the caller.
+    ///
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn from_macro(
+        status: impl TryInto<StatusCode>,
+        domain: impl Domain,
+        message: String,
+    ) -> Self {
+        let location = std::panic::Location::caller();
+        let status = status
+            .try_into()
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut error = Self::categorized(status, domain.as_domain(), message, None);
+        error.context.set_location(location);
+        error
+    }
+
+    ///
+    /// Where in the source this error was created, if it was created by one of
+    /// the [`http_whatever!`], [`http_bail!`] or [`http_ensure!`] macros.
+    ///
+    pub fn location(&self) -> Option<&'static std::panic::Location<'static>> {
+        self.context.location()
+    }
+
+    ///
+    /// Create a new `HttpWhatever` from its separate parts, replacing any `:` in
+    /// the domain by `_`.
+    ///
+    pub(crate) fn categorized(
+        status: StatusCode,
+        domain: &str,
+        message: impl std::fmt::Display,
+        source: Option<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        let message = http_err!(status.as_u16(), domain::sanitized(domain), message);
+        match source {
+            Some(source) => <Self as FromString>::with_source(source, message),
+            None => Self::without_source(message),
+        }
+    }
+
+    ///
+    /// Create a `500 Internal Server Error`, domain "unknown", from any error,
+    /// keeping it as the source and using its `to_string()` output as the message.
+    ///
+    /// This is for the common "just box it and move on" case, where there is
+    /// no useful context to add.
+    ///
+    pub fn from_err(error: impl Error + Send + Sync + 'static) -> Self {
+        Self::from_err_with(StatusCode::INTERNAL_SERVER_ERROR, "unknown", error)
+    }
+
+    ///
+    /// As [`HttpWhatever::from_err`], but with the given status and domain.
+    ///
+    pub fn from_err_with(
+        status: StatusCode,
+        domain: impl Domain,
+        error: impl Error + Send + Sync + 'static,
+    ) -> Self {
+        let message = error.to_string();
+        Self::categorized(status, domain.as_domain(), message, Some(Box::new(error)))
+    }
+
+    ///
+    /// Create a `503 Service Unavailable` error, domain "Overloaded", whose
+    /// responses tell the client to retry after `retry_in`.
+    ///
+    /// This is intended for load-shedding middleware and graceful-degradation
+    /// paths.
+    ///
+    pub fn overloaded(retry_in: Duration) -> Self {
+        Self::categorized(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Overloaded",
+            "The service is temporarily overloaded",
+            None,
+        )
+        .with_retry_after(retry_in)
+    }
+
+    ///
+    /// Create a `429 Too Many Requests` error, domain "RateLimit", whose
+    /// responses tell the client to retry after `retry_in`.
+    ///
+    /// With the `governor` feature, rate limiter rejections convert to this.
+    ///
+    pub fn rate_limited(retry_in: Duration) -> Self {
+        Self::categorized(
+            StatusCode::TOO_MANY_REQUESTS,
+            "RateLimit",
+            "Too many requests",
+            None,
+        )
+        .with_retry_after(retry_in)
+    }
+
+    ///
+    /// Create a new `HttpWhatever` whose message is only formatted, by calling
+    /// `message`, if and when the error is actually rendered or inspected.
+    ///
+    /// This avoids the formatting cost on hot error paths where errors are
+    /// frequently created and then swallowed - by retries, for example.
+    ///
+    /// The string returned by `message` should conform to the structure documented
+    /// in the crate documentation.
+    ///
+    pub fn new_lazy(message: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        let mut error = Self::without_source(String::new());
+        error
+            .context
+            .set_lazy_message(context::LazyMessage::new(message));
+        error
+    }
+
+    ///
+    /// As [`HttpWhatever::new_lazy`], but wrapping a source error.
+    ///
+    pub fn new_lazy_with_source(
+        source: Box<dyn Error + Send + Sync>,
+        message: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Self {
+        let mut error = <Self as FromString>::with_source(source, String::new());
+        error
+            .context
+            .set_lazy_message(context::LazyMessage::new(message));
+        error
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// The optional members of the JSON bodies, as already-serialized JSON: the
+    /// locale, field errors and (with the `serde` feature) data, and for debug
+    /// responses - when `debug` is the style of their backtrace - the details and
+    /// backtrace.
+    ///
+    fn json_extra_members(&self, debug: Option<BacktraceStyle>) -> Vec<(&'static str, String)> {
+        let mut members = Vec::new();
+        if let Some(locale) = self.locale() {
+            members.push(("locale", json::string(locale)));
+        }
+        if !self.field_errors().is_empty() {
+            members.push((
+                "errors",
+                json::array(self.field_errors().iter().map(FieldError::to_json)),
+            ));
+        }
+        #[cfg(feature = "serde")]
+        if let Some(data) = self.data() {
+            members.push(("data", data.to_string()));
+        }
+        if let Some(style) = debug {
+            members.push(("details", json::string(&self.details())));
+            if let Some(backtrace) = self.rendered_backtrace_with(style) {
+                members.push(("backtrace", json::string(&backtrace)));
+            }
+        }
+        members
+    }
+
+    ///
+    /// The metadata that may be sent to clients, with redacted values: all of it
+    /// if `all` is set, otherwise only that under the `exposed` keys.
+    ///
+    fn exposed_metadata(&self, all: bool, exposed: &[String]) -> Vec<(&str, String)> {
+        self.metadata()
+            .iter()
+            .filter(|(key, _)| all || exposed.contains(key))
+            .map(|(key, value)| (key.as_str(), render_text(self.id(), value).into_owned()))
+            .collect()
+    }
+
+    ///
+    /// The JSON body, in the preset of the current [`config`], with the details
+    /// and backtrace of debug responses if `debug` is set.
+    ///
+    fn json_body_for(&self, debug: Option<BacktraceStyle>) -> String {
+        let config = config::current();
+        match config.json_preset {
+            Some(preset) => self.preset_json_body(preset, debug),
+            None => self.json_body_with_details(debug, config.json_status),
+        }
+    }
+}
+
+impl HttpError for HttpWhatever {
+    fn status(&self) -> StatusCode {
+        self.parts().2
+    }
+
+    fn domain(&self) -> &str {
+        self.parts().1
+    }
+
+    fn message(&self) -> Cow<'_, str> {
+        self.rendered_message()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        self.error_headers()
+    }
+
+    fn on_render(&self) {
+        if self.context().first_render() {
+            self.emit_audit_record();
+        }
+    }
+
+    fn json_body(&self) -> String {
+        self.json_body_for(debug_style())
+    }
+}
+
+impl HttpWhatever {
+    fn json_body_with_details(&self, debug: Option<BacktraceStyle>, status: bool) -> String {
+        let parts = self.parts();
+        let mut body = json::JsonObject::new();
+        body.string("message", &self.rendered_message())
+            .string("domain", parts.1);
+        if status {
+            body.number("status", parts.2.as_u16());
+        }
+        body.string("id", self.id().as_str());
+        for (key, value) in self.json_extra_members(debug) {
+            body.raw(key, &value);
+        }
+        body.finish()
+    }
+}
+
+///
+/// The backtrace style of debug responses if the current [`config`] is in
+/// [`Mode::Debug`], otherwise `None`.
+///
+fn debug_style() -> Option<BacktraceStyle> {
+    let config = config::current();
+    (config.mode == Mode::Debug).then_some(config.backtrace_style)
+}
+
+///
+/// Return `true` if the RFC 7240 `Prefer` header asks for `return=minimal`.
+///
+fn prefers_minimal(prefer: Option<&HeaderValue>) -> bool {
+    let Some(prefer) = prefer.and_then(|prefer| prefer.to_str().ok()) else {
+        return false;
+    };
+    prefer.split(',').any(|preference| {
+        let preference = preference.split(';').next().unwrap_or_default();
+        match preference.split_once('=') {
+            Some((token, value)) => {
+                token.trim().eq_ignore_ascii_case("return")
+                    && value
+                        .trim()
+                        .trim_matches('"')
+                        .eq_ignore_ascii_case("minimal")
+            }
+            None => false,
+        }
+    })
+}
+
+///
+/// Percent-encode everything outside printable ASCII, and `%` itself, as the
+/// gRPC `grpc-message` trailer does.
+///
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for b in text.bytes() {
+        if (0x20..0x7f).contains(&b) && b != b'%' {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{b:02X}"));
+        }
+    }
+    encoded
+}
+
+///
+/// Apply the configured [`Redactor`], if any, to `text`, the text of the error
+/// `id`, then the configured secret scan to the result.
+///
+fn render_text<'a>(id: &ErrorId, text: &'a str) -> Cow<'a, str> {
+    let config = config::current();
+    let rendered = match &config.redactor {
+        Some(redactor) => Cow::Owned(redactor.redact(text).into_owned()),
+        None => Cow::Borrowed(text),
+    };
+    secrets::scan(config.secret_scan, id, &rendered);
+    rendered
+}
+
+///
+///  A prelude of the main items required to use this type effectively.
+///
 /// This includes the important items from the [`snafu`] prelude, so _you_ do not
 /// have to include the [`snafu`] prelude.
 ///
 pub mod prelude {
+    pub use crate::ensure_status;
+    pub use crate::http_bail;
+    pub use crate::http_ensure;
     pub use crate::http_err;
+    pub use crate::http_matches;
+    pub use crate::http_whatever;
     pub use crate::HttpResult;
+    pub use crate::HttpResultExt as _;
     pub use crate::HttpWhatever;
+    pub use crate::StatusExt as _;
     pub use snafu::{ensure, OptionExt as _, ResultExt as _};
     pub use snafu::{ensure_whatever, whatever};
 }
@@ -266,6 +1440,7 @@ pub mod prelude {
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
+    use crate::ERROR_ID_HEADER;
     use http::{header::CONTENT_TYPE, Response, StatusCode};
     use std::num::ParseIntError;
 
@@ -291,7 +1466,7 @@ mod tests {
             .whatever_context("400:Input:That was NOT a usize!")
             .unwrap_err();
 
-        assert_eq!(result.details(), "That was NOT a usize!: (Domain: Input, HTTP status: 400 Bad Request)\n[invalid digit found in string]");
+        assert_eq!(result.details(), format!("That was NOT a usize!: (Domain: Input, HTTP status: 400 Bad Request) [error id: {}]\n[invalid digit found in string]", result.id()));
     }
 
     #[test]
@@ -304,6 +1479,14 @@ mod tests {
         assert_eq!(parts.0, "That was NOT a usize!");
         assert_eq!(parts.1, "Input");
         assert_eq!(parts.2, StatusCode::BAD_REQUEST);
+
+        assert_eq!(http_err!("404", "Api", "x"), "404:Api:x");
+        assert_eq!(http_err!(404u16, "Api", "x"), "404:Api:x");
+        assert!(crate::__private::is_valid_status_literal("1_00"));
+        assert!(crate::__private::is_valid_status_literal("0x194"));
+        assert!(!crate::__private::is_valid_status_literal("6000"));
+        assert!(!crate::__private::is_valid_status_literal("99"));
+        assert!(!crate::__private::is_valid_status_literal("-404"));
     }
 
     #[test]
@@ -317,10 +1500,357 @@ mod tests {
         assert_eq!(parts.2, StatusCode::FORBIDDEN);
     }
 
+    #[test]
+    fn test_enrichers() {
+        let mut config = crate::config::Config::default();
+        config
+            .enrichers
+            .push(|context| context.insert_metadata("region", "eu-west-1"));
+        let context = crate::ErrorContext::generate_with(&config);
+
+        assert_eq!(context.metadata().get("region").unwrap(), "eu-west-1");
+    }
+
+    #[test]
+    fn test_is() {
+        let result = HttpWhatever::new(http_err!(404, "Catalog", "No such item"));
+
+        assert!(result.is(StatusCode::NOT_FOUND, "Catalog"));
+        assert!(!result.is(StatusCode::NOT_FOUND, "Orders"));
+        assert!(http_matches!(result, 404, _));
+        assert!(http_matches!(result, _, _));
+        assert!(!http_matches!(result, StatusCode::GONE, "Catalog"));
+    }
+
+    #[test]
+    fn test_chain_queries() {
+        let result: HttpWhatever = parse_usize("certainly not a usize")
+            .whatever_context::<_, HttpWhatever>(http_err!(400, "Database", "Bad key"))
+            .whatever_context(http_err!(500, "Api", "Lookup failed"))
+            .unwrap_err();
+
+        assert!(result.has_domain("Database"));
+        assert!(result.has_domain("Api"));
+        assert!(!result.has_domain("Catalog"));
+        assert!(result.chain_contains::<ParseIntError>());
+        assert!(!result.chain_contains::<std::fmt::Error>());
+        assert_eq!(
+            result
+                .find_in_chain(|e| e.is::<ParseIntError>())
+                .unwrap()
+                .to_string(),
+            "invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_metadata() {
+        let result = HttpWhatever::new(http_err!(400, "Input", "Bad row"))
+            .with_metadata("line", 3)
+            .with_metadata("column", "price");
+
+        assert_eq!(result.metadata().get("line").unwrap(), "3");
+        assert_eq!(result.metadata().get("column").unwrap(), "price");
+    }
+
+    #[test]
+    fn test_status_policy() {
+        use crate::StatusPolicy;
+
+        let result: HttpWhatever =
+            Err::<(), _>(HttpWhatever::new(http_err!(404, "Catalog", "No such item")))
+                .whatever_context("Api:Lookup failed")
+                .unwrap_err();
+
+        let outermost = result.parts_with_policy(StatusPolicy::Outermost);
+        assert_eq!(outermost.1, "Api");
+        assert_eq!(outermost.2, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let innermost = result.parts_with_policy(StatusPolicy::InnermostExplicit);
+        assert_eq!(innermost.1, "Api");
+        assert_eq!(innermost.2, StatusCode::NOT_FOUND);
+
+        // re-wrapping with the two-argument http_err! writes a 500, which does
+        // not count as explicit
+        let result: HttpWhatever =
+            Err::<(), _>(HttpWhatever::new(http_err!(404, "Catalog", "No such item")))
+                .whatever_context::<_, HttpWhatever>(http_err!("Db", "Query failed"))
+                .whatever_context(http_err!("Api", "Lookup failed"))
+                .unwrap_err();
+
+        let outermost = result.parts_with_policy(StatusPolicy::Outermost);
+        assert_eq!(outermost.2, StatusCode::INTERNAL_SERVER_ERROR);
+        let innermost = result.parts_with_policy(StatusPolicy::InnermostExplicit);
+        assert_eq!(innermost.1, "Api");
+        assert_eq!(innermost.2, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_modes_and_formats() {
+        use crate::{BacktraceStyle, ResponseFormat};
+        use http::header::CONTENT_TYPE;
+
+        let error = Err::<(), _>(parse_usize("x").unwrap_err())
+            .whatever_context::<_, HttpWhatever>("400:Input:Bad count")
+            .unwrap_err();
+        assert!(!error
+            .json_body_with_details(None, false)
+            .contains("details"));
+        assert!(error
+            .json_body_with_details(Some(BacktraceStyle::Off), false)
+            .contains("\\n[invalid digit found in string]\"}"));
+
+        let response: Response<String> = error.as_http_formatted_response(ResponseFormat::Text);
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "text/plain");
+        let response: Response<String> = error.as_http_formatted_response(ResponseFormat::Empty);
+        assert!(response.body().is_empty());
+    }
+
+    #[test]
+    fn test_json_escaping() {
+        let error = HttpWhatever::new("400:Input:Bad \"name\" \\ value\nsecond line")
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert_eq!(
+            error.json_body_with_details(None, false),
+            r#"{"message":"Bad \"name\" \\ value\nsecond line","domain":"Input","id":"01ARZ3NDEKTSV4RRFFQ69G5FAV"}"#
+        );
+        assert_eq!(
+            error.json_body_with_details(None, true),
+            r#"{"message":"Bad \"name\" \\ value\nsecond line","domain":"Input","status":400,"id":"01ARZ3NDEKTSV4RRFFQ69G5FAV"}"#
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        use crate::BacktraceStyle;
+
+        let error = HttpWhatever::new("503:Billing:Ledger unavailable");
+        assert_eq!(error.report_with(BacktraceStyle::Off), error.details());
+        assert!(error
+            .report_with(BacktraceStyle::Full)
+            .starts_with(&format!("{}\n\nBacktrace:\n", error.details())));
+    }
+
+    #[test]
+    fn test_http_bail() {
+        fn fail(status: StatusCode) -> HttpResult<()> {
+            http_bail!(status, "Orders", "Order {} failed", 42);
+        }
+        fn fail_invalid() -> HttpResult<()> {
+            http_bail!(1000, "Orders", "Bad status");
+        }
+
+        let error = fail(StatusCode::CONFLICT).unwrap_err();
+        assert_eq!(
+            error.parts(),
+            ("Order 42 failed", "Orders", StatusCode::CONFLICT)
+        );
+        let error = fail_invalid().unwrap_err();
+        assert!(http_matches!(error, 500, "Orders"));
+    }
+
+    #[test]
+    fn test_http_ensure() {
+        fn check(count: usize) -> HttpResult<usize> {
+            http_ensure!(
+                count < 10,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Batch",
+                "{count} items"
+            );
+            Ok(count)
+        }
+
+        assert_eq!(check(3).unwrap(), 3);
+        assert_eq!(
+            check(12).unwrap_err().parts(),
+            ("12 items", "Batch", StatusCode::PAYLOAD_TOO_LARGE)
+        );
+    }
+
+    #[test]
+    fn test_http_whatever() {
+        let line = line!() + 1;
+        let error = http_whatever!(StatusCode::NOT_FOUND, "db", "no row for id {}", 7);
+
+        assert_eq!(
+            error.parts(),
+            ("no row for id 7", "db", StatusCode::NOT_FOUND)
+        );
+        let location = error.location().unwrap();
+        assert_eq!((location.file(), location.line()), (file!(), line));
+        assert!(error
+            .details()
+            .contains(&format!(" [at {}:{line}:", file!())));
+        assert!(HttpWhatever::new("404:db:Gone").location().is_none());
+    }
+
+    #[test]
+    fn test_overloaded() {
+        use http::header::RETRY_AFTER;
+        use std::time::Duration;
+
+        let response: Response<String> =
+            HttpWhatever::overloaded(Duration::from_millis(2500)).as_http_response();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "3");
+
+        let response: Response<String> =
+            HttpWhatever::rate_limited(Duration::from_secs(1)).as_http_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        let response: Response<String> = HttpWhatever::overloaded(Duration::MAX).as_http_response();
+
+        assert_eq!(
+            response.headers().get(RETRY_AFTER).unwrap(),
+            u64::MAX.to_string().as_str()
+        );
+    }
+
+    #[test]
+    fn test_from_err() {
+        let error = HttpWhatever::from_err(parse_usize("x").unwrap_err());
+        assert_eq!(
+            error.parts(),
+            (
+                "invalid digit found in string",
+                "unknown",
+                StatusCode::INTERNAL_SERVER_ERROR
+            )
+        );
+        assert!(error.chain_contains::<ParseIntError>());
+
+        let error = HttpWhatever::from_err_with(
+            StatusCode::BAD_REQUEST,
+            "Input",
+            parse_usize("x").unwrap_err(),
+        );
+        assert!(error.is(StatusCode::BAD_REQUEST, "Input"));
+    }
+
+    #[test]
+    fn test_upstream_headers() {
+        use crate::DEFAULT_UPSTREAM_HEADERS;
+        use http::header::{HeaderMap, SERVER, WWW_AUTHENTICATE};
+
+        let mut upstream = HeaderMap::new();
+        upstream.insert(WWW_AUTHENTICATE, "Bearer".parse().unwrap());
+        upstream.insert(SERVER, "upstream/1.0".parse().unwrap());
+        let response: Response<String> =
+            HttpWhatever::new(http_err!(401, "Upstream", "Unauthorized"))
+                .with_upstream_headers(&upstream, &DEFAULT_UPSTREAM_HEADERS)
+                .as_http_response();
+
+        assert_eq!(response.headers().get(WWW_AUTHENTICATE).unwrap(), "Bearer");
+        assert!(response.headers().get(SERVER).is_none());
+    }
+
+    #[test]
+    fn test_locale() {
+        use http::header::CONTENT_LANGUAGE;
+
+        let response: Response<String> =
+            HttpWhatever::new(http_err!(404, "Catalog", "Article introuvable"))
+                .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+                .with_locale("fr")
+                .as_http_json_response();
+
+        assert_eq!(response.headers().get(CONTENT_LANGUAGE).unwrap(), "fr");
+        assert_eq!(
+            response.body(),
+            "{\"message\":\"Article introuvable\",\"domain\":\"Catalog\",\"id\":\"01ARZ3NDEKTSV4RRFFQ69G5FAV\",\"locale\":\"fr\"}"
+        );
+    }
+
+    #[test]
+    fn test_prefer_minimal() {
+        use crate::PREFERENCE_APPLIED;
+        use http::HeaderValue;
+
+        let result = HttpWhatever::new(http_err!(409, "Catalog", "Already there"));
+        let minimal: Response<String> = result.as_http_response_preferring(Some(
+            &HeaderValue::from_static("respond-async, return=minimal"),
+        ));
+        let full: Response<String> = result
+            .as_http_response_preferring(Some(&HeaderValue::from_static("return=representation")));
+
+        assert_eq!(minimal.status(), StatusCode::CONFLICT);
+        assert_eq!(minimal.body(), "");
+        assert_eq!(
+            minimal.headers().get(PREFERENCE_APPLIED).unwrap(),
+            "return=minimal"
+        );
+        assert!(full.headers().get(PREFERENCE_APPLIED).is_none());
+        assert!(full.body().starts_with("{\"message\":\"Already there\""));
+    }
+
+    #[test]
+    fn test_field_errors() {
+        use crate::FieldError;
+
+        let response: Response<String> =
+            HttpWhatever::new(http_err!(422, "Orders", "Invalid order"))
+                .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+                .with_field_error(FieldError::at(
+                    ["items", "3", "price"],
+                    "range",
+                    "Must be positive",
+                ))
+                .as_http_json_response();
+
+        assert_eq!(
+            response.body(),
+            "{\"message\":\"Invalid order\",\"domain\":\"Orders\",\"id\":\"01ARZ3NDEKTSV4RRFFQ69G5FAV\",\"errors\":[{\"pointer\":\"/items/3/price\",\"code\":\"range\",\"message\":\"Must be positive\"}]}"
+        );
+    }
+
+    #[test]
+    fn test_trailers() {
+        use crate::{STREAM_ERROR_MESSAGE_TRAILER, STREAM_ERROR_STATUS_TRAILER};
+
+        let trailers =
+            HttpWhatever::new(http_err!(503, "Feed", "Lost the 100% feed – sorry")).as_trailers();
+
+        assert_eq!(trailers.get(STREAM_ERROR_STATUS_TRAILER).unwrap(), "503");
+        assert_eq!(
+            trailers.get(STREAM_ERROR_MESSAGE_TRAILER).unwrap(),
+            "Lost the 100%25 feed %E2%80%93 sorry"
+        );
+    }
+
+    #[test]
+    fn test_lazy() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let result: HttpWhatever = parse_usize("certainly not a usize")
+            .whatever_lazy_context(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                http_err!(400, "Input", "That was NOT a usize!")
+            })
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let parts = result.parts();
+        assert_eq!(parts.0, "That was NOT a usize!");
+        assert_eq!(parts.2, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            result.to_string(),
+            "That was NOT a usize!: (Domain: Input, HTTP status: 400 Bad Request)"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn test_response() {
         let result: HttpWhatever =
-            HttpWhatever::new(&http_err!(403, "Input", "That was NOT a usize!"));
+            HttpWhatever::new(&http_err!(403, "Input", "That was NOT a usize!"))
+                .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV");
         let http1: Response<String> = result.as_http_response();
         let http2: Response<String> = result.as_http_string_response();
         let http3: Response<String> = result.as_http_json_response();
@@ -337,7 +1867,11 @@ mod tests {
         );
         assert_eq!(
             http3.body(),
-            "{\"message\":\"That was NOT a usize!\",\"domain\":\"Input\"}"
+            "{\"message\":\"That was NOT a usize!\",\"domain\":\"Input\",\"id\":\"01ARZ3NDEKTSV4RRFFQ69G5FAV\"}"
+        );
+        assert_eq!(
+            http1.headers().get(ERROR_ID_HEADER).unwrap().to_str().unwrap(),
+            "01ARZ3NDEKTSV4RRFFQ69G5FAV"
         );
         assert_eq!(
             http3.headers().get(CONTENT_TYPE).unwrap().to_str().unwrap(),
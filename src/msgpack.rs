@@ -0,0 +1,111 @@
+//!
+//! MessagePack rendering, for services whose whole API is MessagePack.
+//!
+use http::header::CONTENT_TYPE;
+use http::Response;
+
+use crate::{HttpError, HttpWhatever};
+
+fn write_str(out: &mut Vec<u8>, text: &str) {
+    let len = text.len();
+    match len {
+        0..=31 => out.push(0xa0 | len as u8),
+        32..=0xff => out.extend([0xd9, len as u8]),
+        0x100..=0xffff => {
+            out.push(0xda);
+            out.extend((len as u16).to_be_bytes());
+        }
+        _ => {
+            out.push(0xdb);
+            out.extend((len as u32).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn write_uint(out: &mut Vec<u8>, value: u16) {
+    match value {
+        0..=0x7f => out.push(value as u8),
+        0x80..=0xff => out.extend([0xcc, value as u8]),
+        _ => {
+            out.push(0xcd);
+            out.extend(value.to_be_bytes());
+        }
+    }
+}
+
+impl HttpWhatever {
+    fn msgpack_body(&self) -> Vec<u8> {
+        let parts = self.parts();
+        // A fixmap of three entries
+        let mut body = vec![0x83];
+        write_str(&mut body, "message");
+        write_str(&mut body, &self.rendered_message());
+        write_str(&mut body, "domain");
+        write_str(&mut body, parts.1);
+        write_str(&mut body, "status");
+        write_uint(&mut body, parts.2.as_u16());
+        body
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with a
+    /// MessagePack body generated from the `into` method.
+    ///
+    /// The body is a map of the form
+    ///
+    /// `{"message": "<message>", "domain": "<domain>", "status": <status>}`
+    ///
+    /// The `content-type` header of the response will be `application/msgpack`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(Vec<u8>)`, that isn't actually
+    /// possible.
+    pub fn as_http_msgpack_response<B>(&self) -> Response<B>
+    where
+        B: From<Vec<u8>>,
+    {
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "application/msgpack")
+            .body(self.msgpack_body().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_str;
+    use crate::HttpWhatever;
+    use http::header::CONTENT_TYPE;
+    use http::Response;
+
+    #[test]
+    fn msgpack_body() {
+        let response: Response<Vec<u8>> =
+            HttpWhatever::new("404:Api:Gone").as_http_msgpack_response();
+
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/msgpack"
+        );
+        let mut expected = vec![0x83, 0xa7];
+        expected.extend(b"message");
+        expected.push(0xa4);
+        expected.extend(b"Gone");
+        expected.push(0xa6);
+        expected.extend(b"domain");
+        expected.push(0xa3);
+        expected.extend(b"Api");
+        expected.push(0xa6);
+        expected.extend(b"status");
+        expected.extend([0xcd, 0x01, 0x94]);
+        assert_eq!(response.body(), &expected);
+
+        let mut long = Vec::new();
+        write_str(&mut long, &"x".repeat(40));
+        assert_eq!(long[..2], [0xd9, 40]);
+    }
+}
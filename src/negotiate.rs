@@ -0,0 +1,184 @@
+//!
+//! Selection of the response representation from the `Accept` request header.
+//!
+use http::header::VARY;
+use http::{HeaderValue, Response};
+
+use crate::HttpWhatever;
+
+///
+/// The representations an error can be negotiated into.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Representation {
+    Json,
+    Problem,
+    Html,
+    Xml,
+    Text,
+}
+
+///
+/// The offered media types, in order of preference when the client rates
+/// several equally.
+///
+const OFFERED: [(Representation, &str, &str); 5] = [
+    (Representation::Json, "application", "json"),
+    (Representation::Problem, "application", "problem+json"),
+    (Representation::Html, "text", "html"),
+    (Representation::Xml, "application", "xml"),
+    (Representation::Text, "text", "plain"),
+];
+
+///
+/// A media range of an `Accept` header, with its quality in thousandths.
+///
+struct MediaRange<'a> {
+    kind: &'a str,
+    subtype: &'a str,
+    quality: u16,
+}
+
+impl<'a> MediaRange<'a> {
+    fn parse(range: &'a str) -> Option<Self> {
+        let mut params = range.split(';');
+        let (kind, subtype) = params.next()?.trim().split_once('/')?;
+        let quality = params
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+            .map_or(Some(1000), |(_, q)| {
+                q.trim()
+                    .parse::<f32>()
+                    .ok()
+                    .map(|q| (q.clamp(0.0, 1.0) * 1000.0).round() as u16)
+            })?;
+        Some(Self {
+            kind: kind.trim(),
+            subtype: subtype.trim(),
+            quality,
+        })
+    }
+
+    ///
+    /// How specifically the range matches the media type - `None` if it does
+    /// not match at all.
+    ///
+    fn specificity(&self, kind: &str, subtype: &str) -> Option<u8> {
+        match (self.kind, self.subtype) {
+            ("*", "*") => Some(0),
+            (k, "*") if k.eq_ignore_ascii_case(kind) => Some(1),
+            (k, s) if k.eq_ignore_ascii_case(kind) && s.eq_ignore_ascii_case(subtype) => Some(2),
+            _ => None,
+        }
+    }
+}
+
+///
+/// Pick the representation the client rates highest, with JSON for a missing
+/// or unusable `Accept` header, or one accepting nothing on offer.
+///
+fn negotiate(accept: Option<&HeaderValue>) -> Representation {
+    let Some(accept) = accept.and_then(|accept| accept.to_str().ok()) else {
+        return Representation::Json;
+    };
+    let ranges: Vec<_> = accept.split(',').filter_map(MediaRange::parse).collect();
+    let mut best = (Representation::Json, 0);
+    for (representation, kind, subtype) in OFFERED {
+        let quality = ranges
+            .iter()
+            .filter_map(|range| Some((range.specificity(kind, subtype)?, range.quality)))
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(0, |(_, quality)| quality);
+        if quality > best.1 {
+            best = (representation, quality);
+        }
+    }
+    best.0
+}
+
+impl HttpWhatever {
+    ///
+    /// Return an [`http::Response<B>`] representation of the error in the
+    /// format the client prefers, according to the `accept` header of its
+    /// request:
+    ///
+    /// * `application/json` - [`HttpWhatever::as_http_json_response`]
+    /// * `application/problem+json` - [`HttpWhatever::as_problem_json_response`]
+    /// * `text/html` - [`HttpWhatever::as_http_html_response`]
+    /// * `application/xml` - [`HttpWhatever::as_http_xml_response`]
+    /// * `text/plain` - [`HttpWhatever::as_http_string_response`]
+    ///
+    /// Quality values are honored, and formats the client rates equally are
+    /// preferred in the order above. Without an `accept` header, or if it accepts
+    /// none of these formats, the response is JSON. The response carries a
+    /// `Vary: Accept` header.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_negotiated_response<B>(&self, accept: Option<&HeaderValue>) -> Response<B>
+    where
+        B: From<String>,
+    {
+        let mut response = match negotiate(accept) {
+            Representation::Json => self.as_http_json_response(),
+            Representation::Problem => self.as_problem_json_response(),
+            Representation::Html => self.as_http_html_response(),
+            Representation::Xml => self.as_http_xml_response(),
+            Representation::Text => self.as_http_string_response(),
+        };
+        response
+            .headers_mut()
+            .append(VARY, HeaderValue::from_static("accept"));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{negotiate, Representation};
+    use crate::HttpWhatever;
+    use http::header::{CONTENT_TYPE, VARY};
+    use http::{HeaderValue, Response};
+
+    fn negotiated(accept: &'static str) -> Representation {
+        negotiate(Some(&HeaderValue::from_static(accept)))
+    }
+
+    #[test]
+    fn negotiation() {
+        assert_eq!(negotiate(None), Representation::Json);
+        assert_eq!(negotiated("*/*"), Representation::Json);
+        assert_eq!(negotiated("image/png"), Representation::Json);
+        assert_eq!(
+            negotiated("text/html,application/xhtml+xml,*/*;q=0.8"),
+            Representation::Html
+        );
+        assert_eq!(
+            negotiated("application/problem+json, application/json;q=0.9"),
+            Representation::Problem
+        );
+        assert_eq!(negotiated("text/*;q=0.5, text/plain"), Representation::Text);
+        assert_eq!(
+            negotiated("application/xml, application/json;q=0"),
+            Representation::Xml
+        );
+        assert_eq!(
+            negotiated("*/*;q=0.1, application/json;q=0"),
+            Representation::Problem
+        );
+    }
+
+    #[test]
+    fn negotiated_response() {
+        let response: Response<String> = HttpWhatever::new("404:Api:Gone")
+            .as_negotiated_response(Some(&HeaderValue::from_static("text/html")));
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(response.headers().get(VARY).unwrap(), "accept");
+    }
+}
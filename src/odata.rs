@@ -0,0 +1,86 @@
+//!
+//! OData v4 error rendering.
+//!
+use http::header::CONTENT_TYPE;
+use http::Response;
+
+use crate::json::{self, JsonObject};
+use crate::{HttpError, HttpWhatever};
+
+impl HttpWhatever {
+    fn odata_body(&self) -> String {
+        let mut error = JsonObject::new();
+        error
+            .string("code", self.parts().1)
+            .string("message", &self.rendered_message());
+        if !self.field_errors().is_empty() {
+            error.raw(
+                "details",
+                &json::array(self.field_errors().iter().map(|field| {
+                    JsonObject::new()
+                        .string("code", field.code())
+                        .string("message", field.message())
+                        .string("target", field.pointer())
+                        .finish()
+                })),
+            );
+        }
+        JsonObject::new().raw("error", &error.finish()).finish()
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with an
+    /// OData v4 error body generated from the `into` method.
+    ///
+    /// The string in the response body will be of the format
+    ///
+    /// `{"error":{"code":"<domain>","message":"<message>"}}`
+    ///
+    /// Field errors are added as a `details` member, each with its JSON pointer
+    /// as the `target`.
+    ///
+    /// The `content-type` header of the response will be `application/json`,
+    /// and the `OData-Version` header will be `4.0`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_odata_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "application/json")
+            .header("odata-version", "4.0")
+            .body(self.odata_body().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FieldError, HttpWhatever};
+    use http::Response;
+
+    #[test]
+    fn odata_envelope() {
+        let response: Response<String> =
+            HttpWhatever::new("400:Orders:Invalid \"order\"").as_odata_response();
+        assert_eq!(response.headers().get("odata-version").unwrap(), "4.0");
+        assert_eq!(
+            response.body(),
+            r#"{"error":{"code":"Orders","message":"Invalid \"order\""}}"#
+        );
+
+        let response: Response<String> = HttpWhatever::new("400:Orders:Invalid order")
+            .with_field_error(FieldError::at(["quantity"], "range", "Must be positive"))
+            .as_odata_response();
+        assert_eq!(
+            response.body(),
+            r#"{"error":{"code":"Orders","message":"Invalid order","details":[{"code":"range","message":"Must be positive","target":"/quantity"}]}}"#
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//!
+//! Recording of errors on OpenTelemetry spans.
+//!
+use ::opentelemetry::trace::Status;
+use ::tracing::Span;
+use ::tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::HttpWhatever;
+
+impl HttpWhatever {
+    ///
+    /// Record this error on `span`, following the OpenTelemetry semantic
+    /// conventions for exceptions: the span status is set to `ERROR`, with the
+    /// message as its description, and an `exception` event is added carrying
+    /// the `exception.type`, `exception.message` and `exception.stacktrace`
+    /// attributes, plus the `error.id`, `error.domain` and
+    /// `http.response.status_code` of the error.
+    ///
+    /// The span must be recorded by a `tracing_opentelemetry` layer for any of
+    /// this to reach the exporter.
+    ///
+    /// ```ignore
+    /// let error = HttpWhatever::new("503:Billing:Ledger unavailable");
+    /// error.record_on_span(&tracing::Span::current());
+    /// ```
+    ///
+    pub fn record_on_span(&self, span: &Span) {
+        let parts = self.parts();
+        let message = self.rendered_message();
+        span.set_status(Status::error(message.to_string()));
+        ::tracing::error!(
+            parent: span,
+            "exception.type" = std::any::type_name::<Self>(),
+            "exception.message" = %message,
+            "exception.stacktrace" = %self.rendered_backtrace(),
+            "error.id" = %self.id(),
+            "error.domain" = parts.1,
+            "http.response.status_code" = parts.2.as_u16(),
+            "exception"
+        );
+    }
+}
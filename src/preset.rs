@@ -0,0 +1,123 @@
+//!
+//! The JSON body presets.
+//!
+use std::error::Error;
+
+use http::{Response, StatusCode};
+
+use crate::json::{self, JsonObject};
+use crate::{config, render_text, BacktraceStyle, HttpError, HttpWhatever, JsonPreset};
+
+///
+/// The canonical reason of `status` as an upper snake case code, e.g. `NOT_FOUND`.
+///
+fn status_code_name(status: StatusCode) -> String {
+    status
+        .canonical_reason()
+        .unwrap_or("Unknown")
+        .chars()
+        .filter_map(|c| match c {
+            ' ' | '-' => Some('_'),
+            c if c.is_ascii_alphanumeric() => Some(c.to_ascii_uppercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+impl HttpWhatever {
+    pub(crate) fn preset_json_body(
+        &self,
+        preset: JsonPreset,
+        debug: Option<BacktraceStyle>,
+    ) -> String {
+        let mut body = JsonObject::new();
+        body.string("message", &self.rendered_message());
+        if preset == JsonPreset::Minimal {
+            return body.finish();
+        }
+        let parts = self.parts();
+        body.string("domain", parts.1)
+            .number("status", parts.2.as_u16())
+            .string("code", &status_code_name(parts.2))
+            .string("id", self.id().as_str());
+        for (key, value) in self.json_extra_members(debug) {
+            body.raw(key, &value);
+        }
+        if preset == JsonPreset::Verbose {
+            let mut chain = Vec::new();
+            let mut source = self.source();
+            while let Some(e) = source {
+                chain.push(json::string(&render_text(self.id(), &e.to_string())));
+                source = e.source();
+            }
+            body.raw("chain", &json::array(chain));
+            let exposed =
+                self.exposed_metadata(debug.is_some(), &config::current().exposed_metadata);
+            for key in ["trace_id", "help"] {
+                if let Some((_, value)) = exposed.iter().find(|(k, _)| *k == key) {
+                    body.string(key, value);
+                }
+            }
+            if !exposed.is_empty() {
+                let mut extensions = JsonObject::new();
+                for (key, value) in &exposed {
+                    extensions.string(key, value);
+                }
+                body.raw("extensions", &extensions.finish());
+            }
+        }
+        body.finish()
+    }
+
+    ///
+    /// As [`HttpWhatever::as_http_json_response`], but with a body following the
+    /// given [`JsonPreset`] whatever the preset of the current [`config`](crate::config).
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_json_response_with<B>(&self, preset: JsonPreset) -> Response<B>
+    where
+        B: From<String>,
+    {
+        (self as &dyn HttpError)
+            .json_response_builder()
+            .body(self.preset_json_body(preset, crate::debug_style()).into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::{BacktraceStyle, JsonPreset};
+
+    #[test]
+    fn presets() {
+        let error = Err::<(), _>(HttpWhatever::new("404:Catalog:No such item"))
+            .whatever_context::<_, HttpWhatever>("404:Api:Lookup failed")
+            .unwrap_err()
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+            .with_metadata("help", "https://example.com/help");
+
+        assert_eq!(
+            error.preset_json_body(JsonPreset::Minimal, None),
+            r#"{"message":"Lookup failed"}"#
+        );
+        assert_eq!(
+            error.preset_json_body(JsonPreset::Standard, None),
+            r#"{"message":"Lookup failed","domain":"Api","status":404,"code":"NOT_FOUND","id":"01ARZ3NDEKTSV4RRFFQ69G5FAV"}"#
+        );
+        assert_eq!(
+            error.preset_json_body(JsonPreset::Verbose, None),
+            r#"{"message":"Lookup failed","domain":"Api","status":404,"code":"NOT_FOUND","id":"01ARZ3NDEKTSV4RRFFQ69G5FAV","chain":["No such item: (Domain: Catalog, HTTP status: 404 Not Found)"]}"#
+        );
+        // all metadata is exposed in debug responses
+        let body = error.preset_json_body(JsonPreset::Verbose, Some(BacktraceStyle::Off));
+        assert!(body.contains(
+            r#""help":"https://example.com/help","extensions":{"help":"https://example.com/help"}"#
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+//!
+//! RFC 9457 Problem Details rendering.
+//!
+use http::header::CONTENT_TYPE;
+use http::Response;
+
+use crate::json::JsonObject;
+use crate::{HttpError, HttpWhatever};
+
+impl HttpWhatever {
+    fn problem_json_body(&self) -> String {
+        let parts = self.parts();
+        let mut body = JsonObject::new();
+        body.string("type", "about:blank")
+            .string("title", parts.2.canonical_reason().unwrap_or("Unknown"))
+            .number("status", parts.2.as_u16())
+            .string("detail", &self.rendered_message())
+            .string("domain", parts.1)
+            .string("id", self.id().as_str());
+        for (name, value) in self.json_extra_members(None) {
+            body.raw(name, &value);
+        }
+        body.finish()
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with an
+    /// [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457) Problem Details body.
+    ///
+    /// The body will be of the format
+    ///
+    /// `{"type":"about:blank","title":"<reason>","status":<status>,"detail":"<message>","domain":"<domain>","id":"<id>"}`
+    ///
+    /// where the title is the canonical reason of the status. The locale, field
+    /// errors and (with the `serde` feature) data are added as the `locale`,
+    /// `errors` and `data` members, as in
+    /// [`HttpWhatever::as_http_json_response`] - each field error with the JSON
+    /// `pointer` of its field.
+    ///
+    /// The `content-type` header of the response will be `application/problem+json`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_problem_json_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "application/problem+json")
+            .body(self.problem_json_body().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FieldError, HttpWhatever};
+    use http::header::CONTENT_TYPE;
+    use http::Response;
+
+    #[test]
+    fn problem_json() {
+        let response: Response<String> = HttpWhatever::new("422:Orders:Invalid order")
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+            .with_locale("en-GB")
+            .with_field_error(FieldError::at(["items", "0"], "required", "Missing"))
+            .as_problem_json_response();
+
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        assert_eq!(
+            response.body(),
+            r#"{"type":"about:blank","title":"Unprocessable Entity","status":422,"detail":"Invalid order","domain":"Orders","id":"01ARZ3NDEKTSV4RRFFQ69G5FAV","locale":"en-GB","errors":[{"pointer":"/items/0","code":"required","message":"Missing"}]}"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn problem_json_data() {
+        let response: Response<String> = HttpWhatever::new("409:Orders:Out of stock")
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV")
+            .with_data(&serde_json::json!({"sku": "A1"}))
+            .as_problem_json_response();
+
+        assert_eq!(
+            response.body(),
+            r#"{"type":"about:blank","title":"Conflict","status":409,"detail":"Out of stock","domain":"Orders","id":"01ARZ3NDEKTSV4RRFFQ69G5FAV","data":{"sku":"A1"}}"#
+        );
+    }
+}
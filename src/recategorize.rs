@@ -0,0 +1,146 @@
+//!
+//! Recategorization of errors as they propagate through the layers of an
+//! application.
+//!
+use http::StatusCode;
+
+use crate::{http_err, Domain, HttpWhatever};
+
+impl HttpWhatever {
+    ///
+    /// Replace the status and domain of the error, keeping its message, its
+    /// source and its context. A `None` status leaves the error without an
+    /// explicit status. Any `:` in the domain is replaced by `_`.
+    ///
+    fn recategorized(mut self, status: Option<StatusCode>, domain: &str) -> Self {
+        let domain = crate::domain::sanitized(domain);
+        let message = self.parts().0.to_owned();
+        self.message = match status {
+            Some(status) => http_err!(status.as_u16(), domain, message),
+            None => format!("{domain}:{message}"),
+        };
+        self.context.clear_lazy_message();
+        self
+    }
+
+    ///
+    /// Override the status of the error, keeping its domain, message, source and
+    /// context - so that a higher layer can recategorize it without rebuilding
+    /// the message.
+    ///
+    pub fn with_status(self, status: StatusCode) -> Self {
+        let domain = self.parts().1.to_owned();
+        self.recategorized(Some(status), &domain)
+    }
+
+    ///
+    /// Override the status of the error with the result of `f` on its current
+    /// status - for a gateway downgrading a `500` to a `502`, for example.
+    ///
+    /// ```rust
+    /// use http::StatusCode;
+    /// use http_whatever::HttpWhatever;
+    ///
+    /// let error = HttpWhatever::new("500:Upstream:Connection reset").map_status(|status| {
+    ///     if status.is_server_error() {
+    ///         StatusCode::BAD_GATEWAY
+    ///     } else {
+    ///         status
+    ///     }
+    /// });
+    ///
+    /// assert!(error.is(StatusCode::BAD_GATEWAY, "Upstream"));
+    /// ```
+    ///
+    pub fn map_status(self, f: impl FnOnce(StatusCode) -> StatusCode) -> Self {
+        let status = f(self.parts().2);
+        self.with_status(status)
+    }
+
+    ///
+    /// Override the domain of the error, keeping its status, message, source and
+    /// context - so that an edge layer can relabel low-level errors under its own
+    /// domain.
+    ///
+    pub fn with_domain(self, domain: impl Domain) -> Self {
+        let status = self.explicit_status();
+        self.recategorized(status, domain.as_domain())
+    }
+
+    ///
+    /// Override the domain of the error with the result of `f` on its current
+    /// domain.
+    ///
+    /// ```rust
+    /// use http::StatusCode;
+    /// use http_whatever::HttpWhatever;
+    ///
+    /// let error = HttpWhatever::new("404:Catalog:No such item")
+    ///     .map_domain(|domain| format!("Shop.{domain}"));
+    ///
+    /// assert!(error.is(StatusCode::NOT_FOUND, "Shop.Catalog"));
+    /// ```
+    ///
+    pub fn map_domain<D>(self, f: impl FnOnce(&str) -> D) -> Self
+    where
+        D: Domain,
+    {
+        let domain = f(self.parts().1);
+        self.with_domain(domain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpWhatever;
+    use http::StatusCode;
+
+    #[test]
+    fn status_override() {
+        let error = HttpWhatever::new("404:Catalog:No such item");
+        let id = error.id().clone();
+        let error = error.with_status(StatusCode::GONE);
+
+        assert_eq!(error.parts(), ("No such item", "Catalog", StatusCode::GONE));
+        assert_eq!(error.id(), &id);
+
+        let error = HttpWhatever::new_lazy(|| "500:Db:Timed out".to_owned())
+            .map_status(|_| StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            error.parts(),
+            ("Timed out", "Db", StatusCode::SERVICE_UNAVAILABLE)
+        );
+    }
+
+    #[test]
+    fn domain_override() {
+        let error = HttpWhatever::new("409:Db:Duplicate key").with_domain("Orders");
+        assert_eq!(
+            error.parts(),
+            ("Duplicate key", "Orders", StatusCode::CONFLICT)
+        );
+
+        let error = HttpWhatever::new("Duplicate key").with_domain("Orders");
+        assert_eq!(
+            error.parts(),
+            ("Duplicate key", "Orders", StatusCode::INTERNAL_SERVER_ERROR)
+        );
+        assert_eq!(error.explicit_status(), None);
+
+        let error = HttpWhatever::new("404:Catalog:No such item")
+            .map_domain(|domain| format!("{domain}:sub"));
+        assert_eq!(
+            error.parts(),
+            ("No such item", "Catalog_sub", StatusCode::NOT_FOUND)
+        );
+        let error = HttpWhatever::new("Duplicate key").with_domain("db:main");
+        assert_eq!(
+            error.parts(),
+            (
+                "Duplicate key",
+                "db_main",
+                StatusCode::INTERNAL_SERVER_ERROR
+            )
+        );
+    }
+}
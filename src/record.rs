@@ -0,0 +1,103 @@
+//!
+//! Lightweight categorization records left in `Extensions` for later middleware.
+//!
+use http::{Extensions, StatusCode};
+
+use crate::HttpError;
+
+///
+/// The categorization of an error, stored in the `Extensions` of every response
+/// rendered from an [`HttpError`] - and, with
+/// [`HttpWhatever::record_into`](crate::HttpWhatever::record_into), of a
+/// request - so that downstream layers (access logging, rate-limit accounting)
+/// can act on it without re-parsing bodies.
+///
+/// The `http` responses of this crate carry it, as do those of the actix-web,
+/// axum, gotham and viz integrations. The ntex, rocket and warp integrations
+/// convert responses to types without compatible extensions, so their
+/// responses do not.
+///
+/// ```
+/// use http::Response;
+/// use http_whatever::{ErrorRecord, HttpWhatever};
+///
+/// let response: Response<String> = HttpWhatever::new("429:Quota:Slow down").as_http_json_response();
+/// let record = response.extensions().get::<ErrorRecord>().unwrap();
+/// assert_eq!(record.domain, "Quota");
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorRecord {
+    ///
+    /// The HTTP status of the error.
+    ///
+    pub status: StatusCode,
+    ///
+    /// The domain of the error.
+    ///
+    pub domain: String,
+    ///
+    /// A stable hash of the status and domain of the error, identifying errors of
+    /// the same kind across instances and processes - whatever the values
+    /// interpolated into their messages.
+    ///
+    pub fingerprint: u64,
+}
+
+impl ErrorRecord {
+    pub(crate) fn of(error: &(impl HttpError + ?Sized)) -> Self {
+        let status = error.status();
+        let domain = error.domain();
+        let mut hash = fnv1a(FNV_OFFSET, status.as_str().as_bytes());
+        hash = fnv1a(hash, &[0]);
+        hash = fnv1a(hash, domain.as_bytes());
+        Self {
+            status,
+            domain: domain.to_owned(),
+            fingerprint: hash,
+        }
+    }
+
+    ///
+    /// Insert this record into `extensions`, replacing any previous record.
+    ///
+    pub fn insert_into(self, extensions: &mut Extensions) {
+        extensions.insert(self);
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+///
+/// Continue an FNV-1a hash over `bytes`.
+///
+pub(crate) fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorRecord;
+    use crate::HttpWhatever;
+    use http::{Extensions, StatusCode};
+
+    #[test]
+    fn records() {
+        let first = HttpWhatever::new("429:Quota:No quota left for 42");
+        let second = HttpWhatever::new("429:Quota:No quota left for 43");
+        let other = HttpWhatever::new("429:Billing:No quota left for 42");
+
+        let mut extensions = Extensions::new();
+        first.record_into(&mut extensions);
+        let record = extensions.get::<ErrorRecord>().unwrap();
+
+        assert_eq!(record.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(record, &ErrorRecord::of(&second));
+        assert_ne!(record.fingerprint, ErrorRecord::of(&other).fingerprint);
+    }
+}
@@ -0,0 +1,235 @@
+//!
+//! Redaction of sensitive data from error text.
+//!
+use std::borrow::Cow;
+use std::ops::Range;
+
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+///
+/// A redaction step applied to error messages and source strings before they
+/// reach response bodies and logs.
+///
+/// Any `Fn(&str) -> String` closure is a `Redactor`, which makes it simple to plug
+/// in, for example, a set of regular expressions.
+///
+pub trait Redactor: Send + Sync {
+    ///
+    /// Return `text` with any sensitive data removed.
+    ///
+    fn redact<'a>(&self, text: &'a str) -> Cow<'a, str>;
+}
+
+impl<F> Redactor for F
+where
+    F: Fn(&str) -> String + Send + Sync,
+{
+    fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        Cow::Owned(self(text))
+    }
+}
+
+///
+/// A [`Redactor`] that replaces email addresses, bearer tokens, and payment card
+/// numbers (13 to 19 digits, optionally grouped with spaces or dashes, passing
+/// the Luhn check) with `[REDACTED]`.
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardRedactor;
+
+impl Redactor for StandardRedactor {
+    fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let without_cards = redact_card_numbers(text);
+        match redact_words(&without_cards) {
+            Some(redacted) => Cow::Owned(redacted),
+            None => match without_cards {
+                Cow::Borrowed(_) => Cow::Borrowed(text),
+                Cow::Owned(s) => Cow::Owned(s),
+            },
+        }
+    }
+}
+
+// `u32::is_multiple_of` needs Rust 1.87.
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn luhn_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let d = d * 2;
+                if d > 9 {
+                    d - 9
+                } else {
+                    d
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+fn redact_card_numbers(text: &str) -> Cow<'_, str> {
+    let groups = digit_groups(text);
+    let mut cards = Vec::new();
+    let mut i = 0;
+    while i < groups.len() {
+        match card_window(text, &groups[i..]) {
+            Some(n) => {
+                cards.push(groups[i].start..groups[i + n - 1].end);
+                i += n;
+            }
+            None => i += 1,
+        }
+    }
+    if cards.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    for card in cards {
+        out.push_str(&text[copied..card.start]);
+        out.push_str(REDACTED);
+        copied = card.end;
+    }
+    out.push_str(&text[copied..]);
+    Cow::Owned(out)
+}
+
+///
+/// The byte ranges of the runs of ASCII digits in `text`.
+///
+fn digit_groups(text: &str) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = None;
+    for (i, b) in text.bytes().enumerate() {
+        match (b.is_ascii_digit(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                groups.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        groups.push(s..text.len());
+    }
+    groups
+}
+
+///
+/// The number of `groups`, from the first, making up the longest card number:
+/// either a single group or groups of 3 to 6 digits each separated by one space
+/// or dash, 13 to 19 digits in all, passing the Luhn check.
+///
+fn card_window(text: &str, groups: &[Range<usize>]) -> Option<usize> {
+    let mut digits = Vec::new();
+    let mut window = None;
+    for (n, group) in groups.iter().enumerate() {
+        if n > 0 {
+            let previous = &groups[n - 1];
+            let grouped = |group: &Range<usize>| (3..=6).contains(&group.len());
+            if group.start != previous.end + 1
+                || !matches!(text.as_bytes()[previous.end], b' ' | b'-')
+                || !grouped(group)
+                || !grouped(&groups[0])
+            {
+                break;
+            }
+        }
+        digits.extend(text[group.clone()].bytes().map(|b| u32::from(b - b'0')));
+        if digits.len() > 19 {
+            break;
+        }
+        if digits.len() >= 13 && luhn_valid(&digits) {
+            window = Some(n + 1);
+        }
+    }
+    window
+}
+
+fn is_email(word: &str) -> bool {
+    match word.split_once('@') {
+        Some((local, host)) => {
+            !local.is_empty()
+                && host.contains('.')
+                && !host.starts_with('.')
+                && !host.ends_with('.')
+                && !host.contains('@')
+        }
+        None => false,
+    }
+}
+
+fn redact_words(text: &str) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut after_bearer = false;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (word, tail) = rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len()));
+        let (space, tail) = tail.split_at(
+            tail.find(|c: char| !c.is_whitespace())
+                .unwrap_or(tail.len()),
+        );
+        rest = tail;
+        if !word.is_empty() {
+            let trimmed = word.trim_matches(|c: char| {
+                matches!(c, ',' | ';' | '(' | ')' | '<' | '>' | '"' | '\'')
+            });
+            if !trimmed.is_empty() && (after_bearer || is_email(trimmed)) {
+                out.push_str(&word.replacen(trimmed, REDACTED, 1));
+                changed = true;
+            } else {
+                out.push_str(word);
+            }
+            after_bearer = trimmed.eq_ignore_ascii_case("bearer");
+        }
+        out.push_str(space);
+    }
+    changed.then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Redactor, StandardRedactor};
+
+    #[test]
+    fn standard_redactor() {
+        let redactor = StandardRedactor;
+
+        assert_eq!(
+            redactor.redact("No user named jane.doe@example.com (or Bearer abc.def)"),
+            "No user named [REDACTED] (or Bearer [REDACTED])"
+        );
+        assert_eq!(
+            redactor.redact("Card 4111 1111 1111 1111 declined, order 12345"),
+            "Card [REDACTED] declined, order 12345"
+        );
+        assert_eq!(
+            redactor.redact("Card 4111 1111 1111 1111 - 12 items"),
+            "Card [REDACTED] - 12 items"
+        );
+        assert_eq!(
+            redactor.redact("Order 42 4111111111111111 declined"),
+            "Order 42 [REDACTED] declined"
+        );
+        assert_eq!(
+            redactor.redact("Rejected Bearer  abc.def"),
+            "Rejected Bearer  [REDACTED]"
+        );
+        assert_eq!(
+            redactor.redact("Rejected\tBearer\tabc.def"),
+            "Rejected\tBearer\t[REDACTED]"
+        );
+        assert_eq!(
+            redactor.redact("Nothing to see here"),
+            "Nothing to see here"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//!
+//! Pluggable body renderers, for formats the crate does not provide.
+//!
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use http::header::CONTENT_TYPE;
+use http::{HeaderValue, Response, StatusCode};
+
+use crate::{ErrorId, FieldError, HttpError, HttpWhatever};
+
+///
+/// The parts of an error available to a [`BodyRenderer`].
+///
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ErrorParts<'a> {
+    ///
+    /// The HTTP status of the error.
+    ///
+    pub status: StatusCode,
+    ///
+    /// The domain of the error.
+    ///
+    pub domain: &'a str,
+    ///
+    /// The (redacted) message of the error.
+    ///
+    pub message: Cow<'a, str>,
+    ///
+    /// The identifier of the error.
+    ///
+    pub id: &'a ErrorId,
+    ///
+    /// The field-level validation failures of the error.
+    ///
+    pub field_errors: &'a [FieldError],
+    ///
+    /// The metadata of the error.
+    ///
+    pub metadata: &'a BTreeMap<String, String>,
+}
+
+///
+/// A renderer of error response bodies in a custom format - a company-specific
+/// envelope, for example - used with [`HttpWhatever::as_http_response_using`].
+///
+/// Closures taking the [`ErrorParts`] and returning the content type and body
+/// are renderers.
+///
+/// ```
+/// use http::{HeaderValue, Response};
+/// use http_whatever::{ErrorParts, HttpWhatever};
+///
+/// let envelope = |parts: &ErrorParts| {
+///     (
+///         HeaderValue::from_static("text/plain"),
+///         format!("FAILED {} [{}]", parts.status.as_u16(), parts.domain),
+///     )
+/// };
+/// let response: Response<String> =
+///     HttpWhatever::new("404:Catalog:No such item").as_http_response_using(&envelope);
+///
+/// assert_eq!(response.body(), "FAILED 404 [Catalog]");
+/// ```
+///
+pub trait BodyRenderer {
+    ///
+    /// Return the content type and body of the response for an error.
+    ///
+    fn render(&self, parts: &ErrorParts<'_>) -> (HeaderValue, String);
+}
+
+impl<F> BodyRenderer for F
+where
+    F: Fn(&ErrorParts<'_>) -> (HeaderValue, String),
+{
+    fn render(&self, parts: &ErrorParts<'_>) -> (HeaderValue, String) {
+        self(parts)
+    }
+}
+
+impl HttpWhatever {
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with the
+    /// body and `content-type` header produced by the given [`BodyRenderer`],
+    /// and the status and other headers of the standard responses.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_response_using<R, B>(&self, renderer: &R) -> Response<B>
+    where
+        R: BodyRenderer + ?Sized,
+        B: From<String>,
+    {
+        let (_, domain, status) = self.parts();
+        let (content_type, body) = renderer.render(&ErrorParts {
+            status,
+            domain,
+            message: self.rendered_message(),
+            id: self.id(),
+            field_errors: self.field_errors(),
+            metadata: self.metadata(),
+        });
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, content_type)
+            .body(body.into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BodyRenderer, ErrorParts};
+    use crate::{HttpWhatever, ERROR_ID_HEADER};
+    use http::header::CONTENT_TYPE;
+    use http::{HeaderValue, Response};
+
+    struct Envelope;
+
+    impl BodyRenderer for Envelope {
+        fn render(&self, parts: &ErrorParts<'_>) -> (HeaderValue, String) {
+            (
+                HeaderValue::from_static("application/vnd.acme.error"),
+                format!(
+                    "{}|{}|{}",
+                    parts.status.as_u16(),
+                    parts.domain,
+                    parts.message
+                ),
+            )
+        }
+    }
+
+    #[test]
+    fn custom_renderer() {
+        let response: Response<String> =
+            HttpWhatever::new("409:Orders:Already shipped").as_http_response_using(&Envelope);
+
+        assert_eq!(response.status(), 409);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/vnd.acme.error"
+        );
+        assert!(response.headers().contains_key(ERROR_ID_HEADER));
+        assert_eq!(response.body(), "409|Orders|Already shipped");
+    }
+}
@@ -0,0 +1,122 @@
+//!
+//! Capture of the runtime context an error was created in.
+//!
+use core::fmt::{self, Display};
+use std::sync::OnceLock;
+
+///
+/// Where an error was created: the executing thread and (with the `tokio`
+/// feature) task, and the host and pod names. Essential when aggregating
+/// errors from many replicas.
+///
+/// Capture is enabled with [`Config::capture_runtime_context`](crate::config::Config::capture_runtime_context).
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeContext {
+    thread: String,
+    task: Option<String>,
+    host: Option<&'static str>,
+    pod: Option<&'static str>,
+}
+
+impl RuntimeContext {
+    ///
+    /// Capture the context of the current thread (and task).
+    ///
+    pub fn capture() -> Self {
+        let current = std::thread::current();
+        let thread = match current.name() {
+            Some(name) => name.to_owned(),
+            None => format!("{:?}", current.id()),
+        };
+        #[cfg(feature = "tokio")]
+        let task = tokio::task::try_id().map(|id| id.to_string());
+        #[cfg(not(feature = "tokio"))]
+        let task = None;
+
+        Self {
+            thread,
+            task,
+            host: host_name(),
+            pod: pod_name(),
+        }
+    }
+
+    ///
+    /// The name (or, failing that, the id) of the thread.
+    ///
+    pub fn thread(&self) -> &str {
+        &self.thread
+    }
+
+    ///
+    /// The id of the tokio task, if there was one (requires the `tokio` feature).
+    ///
+    pub fn task(&self) -> Option<&str> {
+        self.task.as_deref()
+    }
+
+    ///
+    /// The host name, from the `HOSTNAME` (or `COMPUTERNAME`) environment variable.
+    ///
+    pub fn host(&self) -> Option<&str> {
+        self.host
+    }
+
+    ///
+    /// The Kubernetes pod name, from the `POD_NAME` environment variable.
+    ///
+    pub fn pod(&self) -> Option<&str> {
+        self.pod
+    }
+}
+
+impl Display for RuntimeContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "thread: {}", self.thread)?;
+        if let Some(task) = &self.task {
+            write!(f, ", task: {task}")?;
+        }
+        if let Some(host) = self.host {
+            write!(f, ", host: {host}")?;
+        }
+        if let Some(pod) = self.pod {
+            write!(f, ", pod: {pod}")?;
+        }
+        Ok(())
+    }
+}
+
+fn host_name() -> Option<&'static str> {
+    static HOST: OnceLock<Option<String>> = OnceLock::new();
+    HOST.get_or_init(|| {
+        std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .ok()
+    })
+    .as_deref()
+}
+
+fn pod_name() -> Option<&'static str> {
+    static POD: OnceLock<Option<String>> = OnceLock::new();
+    POD.get_or_init(|| std::env::var("POD_NAME").ok())
+        .as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RuntimeContext;
+
+    #[test]
+    fn captures_thread() {
+        let context = std::thread::Builder::new()
+            .name("worker-1".to_owned())
+            .spawn(RuntimeContext::capture)
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert_eq!(context.thread(), "worker-1");
+        assert!(context.to_string().starts_with("thread: worker-1"));
+    }
+}
@@ -0,0 +1,230 @@
+//!
+//! `serde`-based rendering, and serialization of the errors themselves.
+//!
+use core::fmt;
+use std::error::Error;
+
+use http::{header::CONTENT_TYPE, Response, StatusCode};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{render_text, HttpError, HttpWhatever};
+
+impl HttpWhatever {
+    ///
+    /// Attach arbitrary structured data to the error, to be embedded under a
+    /// `data` member by the JSON renderers. This lets deep call sites provide
+    /// structured context without knowing how the error will eventually be
+    /// rendered.
+    ///
+    /// Data that fails to serialize is recorded as the serialization error
+    /// message, as a JSON string.
+    ///
+    pub fn with_data<T>(mut self, data: &T) -> Self
+    where
+        T: Serialize + ?Sized,
+    {
+        let data = serde_json::to_value(data).unwrap_or_else(|e| Value::String(e.to_string()));
+        self.context.set_data(data);
+        self
+    }
+
+    ///
+    /// Return the structured data attached to the error, if any.
+    ///
+    pub fn data(&self) -> Option<&Value> {
+        self.context.data()
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with a JSON
+    /// body combining the standard error fields with an arbitrary serializable
+    /// `payload` - partial results or context data, for example.
+    ///
+    /// If the payload serializes to a JSON object, the standard `message`, `domain`
+    /// and `id` fields are merged into it (replacing any members with the same
+    /// names). Otherwise the payload is placed under a `data` member alongside
+    /// them. Data attached with [`HttpWhatever::with_data`] is added under `data`
+    /// unless the payload already provides that member. Should the payload fail
+    /// to serialize, the response is that of
+    /// [`HttpWhatever::as_http_json_response`].
+    ///
+    /// The `content-type` header of the response will be `application/json`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_response_serialized<T, B>(&self, payload: &T) -> Response<B>
+    where
+        T: Serialize + ?Sized,
+        B: From<String>,
+    {
+        let payload = match serde_json::to_value(payload) {
+            Ok(payload) => payload,
+            Err(_) => return self.as_http_json_response(),
+        };
+        let mut body = match payload {
+            Value::Object(members) => members,
+            data => {
+                let mut members = Map::new();
+                members.insert("data".to_owned(), data);
+                members
+            }
+        };
+        let parts = self.parts();
+        body.insert(
+            "message".to_owned(),
+            Value::String(self.rendered_message().into_owned()),
+        );
+        body.insert("domain".to_owned(), Value::String(parts.1.to_owned()));
+        body.insert("id".to_owned(), Value::String(self.id().to_string()));
+        if let Some(data) = self.data() {
+            body.entry("data").or_insert_with(|| data.clone());
+        }
+
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Value::Object(body).to_string().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+///
+/// A source error received in serialized form, of which only the message is
+/// known.
+///
+#[derive(Debug)]
+struct RemoteSource {
+    message: String,
+    source: Option<Box<RemoteSource>>,
+}
+
+impl fmt::Display for RemoteSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for RemoteSource {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+///
+/// The serialized form of an `HttpWhatever`.
+///
+#[derive(Deserialize)]
+struct SerializedError {
+    status: u16,
+    domain: String,
+    message: String,
+    id: Option<String>,
+    #[serde(default)]
+    chain: Vec<String>,
+}
+
+///
+/// An error serializes as
+///
+/// `{"status":<status>,"domain":"<domain>","message":"<message>","id":"<id>","chain":["<source>",...]}`
+///
+/// with the message and sources redacted as for responses, so that it can pass
+/// through message queues and between services.
+///
+impl Serialize for HttpWhatever {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let parts = self.parts();
+        let mut chain = Vec::new();
+        let mut source = self.source();
+        while let Some(e) = source {
+            chain.push(render_text(self.id(), &e.to_string()).into_owned());
+            source = e.source();
+        }
+        let mut state = serializer.serialize_struct("HttpWhatever", 5)?;
+        state.serialize_field("status", &parts.2.as_u16())?;
+        state.serialize_field("domain", parts.1)?;
+        state.serialize_field("message", &*self.rendered_message())?;
+        state.serialize_field("id", self.id().as_str())?;
+        state.serialize_field("chain", &chain)?;
+        state.end()
+    }
+}
+
+///
+/// An error deserializes from its serialized form, keeping its [`ErrorId`](crate::ErrorId)
+/// and with its source chain rebuilt from the source messages. A domain
+/// containing a `:` is rejected.
+///
+impl<'de> Deserialize<'de> for HttpWhatever {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let serialized = SerializedError::deserialize(deserializer)?;
+        let status = StatusCode::from_u16(serialized.status).map_err(serde::de::Error::custom)?;
+        if serialized.domain.contains(':') {
+            return Err(serde::de::Error::custom(format_args!(
+                "invalid domain {:?}: a domain cannot contain ':'",
+                serialized.domain
+            )));
+        }
+        let source = serialized
+            .chain
+            .into_iter()
+            .rev()
+            .fold(None, |source, message| {
+                Some(Box::new(RemoteSource { message, source }))
+            })
+            .map(|source| source as Box<dyn Error + Send + Sync>);
+        let error =
+            HttpWhatever::categorized(status, &serialized.domain, serialized.message, source);
+        Ok(match serialized.id {
+            Some(id) => error.with_id(id),
+            None => error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use http::StatusCode;
+
+    #[test]
+    fn round_trip() {
+        let error = Err::<(), _>(HttpWhatever::new("404:Catalog:No such item"))
+            .whatever_context::<_, HttpWhatever>("404:Api:Lookup failed")
+            .unwrap_err()
+            .with_id("01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            json,
+            r#"{"status":404,"domain":"Api","message":"Lookup failed","id":"01ARZ3NDEKTSV4RRFFQ69G5FAV","chain":["No such item: (Domain: Catalog, HTTP status: 404 Not Found)"]}"#
+        );
+
+        let error: HttpWhatever = serde_json::from_str(&json).unwrap();
+        assert!(error.is(StatusCode::NOT_FOUND, "Api"));
+        assert_eq!(error.id().as_str(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert_eq!(
+            std::error::Error::source(&error).unwrap().to_string(),
+            "No such item: (Domain: Catalog, HTTP status: 404 Not Found)"
+        );
+    }
+
+    #[test]
+    fn domain_with_colon() {
+        let json = r#"{"status":404,"domain":"Api:v2","message":"Lookup failed"}"#;
+        let error = serde_json::from_str::<HttpWhatever>(json).unwrap_err();
+        assert!(error.to_string().contains("cannot contain ':'"));
+    }
+}
@@ -0,0 +1,159 @@
+//!
+//! Capture of `tracing` span fields into errors.
+//!
+use core::fmt::Debug;
+use std::collections::BTreeMap;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+use crate::ErrorContext;
+
+///
+/// A [`tracing_subscriber`] layer that records selected fields of every span,
+/// so that errors created inside those spans carry them as metadata - bridging
+/// span context (`request_id`, `user_id`, `route`...) into error bodies and logs
+/// emitted elsewhere.
+///
+/// When errors are created, the recorded fields of the current span and all of its
+/// parents are copied into the [`ErrorContext`] metadata, with the innermost span
+/// winning when a field is recorded by several.
+///
+/// ```ignore
+/// use tracing_subscriber::prelude::*;
+/// use http_whatever::SpanFieldsLayer;
+///
+/// tracing_subscriber::registry()
+///     .with(SpanFieldsLayer::new(["request_id", "user_id", "route"]))
+///     .with(tracing_subscriber::fmt::layer())
+///     .init();
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct SpanFieldsLayer {
+    fields: Vec<&'static str>,
+}
+
+impl SpanFieldsLayer {
+    ///
+    /// Create a layer recording the named span fields.
+    ///
+    pub fn new(fields: impl IntoIterator<Item = &'static str>) -> Self {
+        Self {
+            fields: fields.into_iter().collect(),
+        }
+    }
+}
+
+///
+/// The selected fields recorded for one span, kept in the span extensions.
+///
+struct CapturedFields(BTreeMap<&'static str, String>);
+
+struct FieldVisitor<'a> {
+    wanted: &'a [&'static str],
+    captured: &'a mut BTreeMap<&'static str, String>,
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if self.wanted.contains(&field.name()) {
+            self.captured.insert(field.name(), value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if self.wanted.contains(&field.name()) {
+            self.captured.insert(field.name(), format!("{value:?}"));
+        }
+    }
+}
+
+impl<S> Layer<S> for SpanFieldsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut captured = BTreeMap::new();
+        attrs.record(&mut FieldVisitor {
+            wanted: &self.fields,
+            captured: &mut captured,
+        });
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(CapturedFields(captured));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            if let Some(CapturedFields(captured)) = extensions.get_mut::<CapturedFields>() {
+                values.record(&mut FieldVisitor {
+                    wanted: &self.fields,
+                    captured,
+                });
+            }
+        }
+    }
+}
+
+///
+/// Copy the fields recorded by a [`SpanFieldsLayer`] for the current span and
+/// its parents into the metadata of `context`.
+///
+pub(crate) fn capture(context: &mut ErrorContext) {
+    tracing::Span::current().with_subscriber(|(id, dispatch)| {
+        let Some(registry) = dispatch.downcast_ref::<Registry>() else {
+            return;
+        };
+        let Some(span) = registry.span(id) else {
+            return;
+        };
+        for span in span.scope().from_root() {
+            if let Some(CapturedFields(captured)) = span.extensions().get::<CapturedFields>() {
+                for (name, value) in captured {
+                    context.insert_metadata(*name, value);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::field::Empty;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::SpanFieldsLayer;
+    use crate::HttpWhatever;
+
+    #[test]
+    fn span_fields() {
+        let subscriber = tracing_subscriber::registry().with(SpanFieldsLayer::new([
+            "request_id",
+            "user_id",
+            "route",
+        ]));
+        tracing::subscriber::with_default(subscriber, || {
+            let outer =
+                tracing::info_span!("request", request_id = "r-1", route = "/items", size = 3);
+            let _outer = outer.enter();
+            let inner = tracing::info_span!("lookup", route = "/items/{id}", user_id = Empty);
+            let _inner = inner.enter();
+            inner.record("user_id", 42);
+
+            let error = HttpWhatever::not_found("Catalog", "No such item");
+            let metadata = error.metadata();
+            assert_eq!(metadata["request_id"], "r-1");
+            assert_eq!(metadata["route"], "/items/{id}");
+            assert_eq!(metadata["user_id"], "42");
+            assert!(!metadata.contains_key("size"));
+        });
+
+        let error = HttpWhatever::not_found("Catalog", "No such item");
+        assert!(error.metadata().is_empty());
+    }
+}
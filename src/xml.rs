@@ -0,0 +1,68 @@
+//!
+//! XML rendering, for legacy clients that only accept XML.
+//!
+use http::header::CONTENT_TYPE;
+use http::Response;
+
+use crate::html::escape;
+use crate::{HttpError, HttpWhatever};
+
+impl HttpWhatever {
+    fn xml_body(&self) -> String {
+        let parts = self.parts();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><error><message>{}</message><domain>{}</domain><status>{}</status></error>",
+            escape(&self.rendered_message()),
+            escape(parts.1),
+            parts.2.as_u16()
+        )
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with an
+    /// XML body generated from the `into` method.
+    ///
+    /// The string in the response body will be of the format
+    ///
+    /// `<?xml version="1.0" encoding="UTF-8"?><error><message>message</message><domain>domain</domain><status>status</status></error>`
+    ///
+    /// The `content-type` header of the response will be `application/xml`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_xml_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "application/xml")
+            .body(self.xml_body().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpWhatever;
+    use http::header::CONTENT_TYPE;
+    use http::Response;
+
+    #[test]
+    fn xml_document() {
+        let response: Response<String> =
+            HttpWhatever::new("409:Orders:Order <42> & 'friends'").as_http_xml_response();
+
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/xml"
+        );
+        assert_eq!(
+            response.body(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><error><message>Order &lt;42&gt; &amp; &#39;friends&#39;</message><domain>Orders</domain><status>409</status></error>"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//!
+//! YAML rendering, for tools and operations APIs.
+//!
+use http::header::CONTENT_TYPE;
+use http::Response;
+
+use crate::{json, HttpError, HttpWhatever};
+
+impl HttpWhatever {
+    fn yaml_body(&self) -> String {
+        // JSON strings are valid YAML double-quoted scalars, with the same escapes
+        let parts = self.parts();
+        format!(
+            "message: {}\ndomain: {}\nstatus: {}\n",
+            json::string(&self.rendered_message()),
+            json::string(parts.1),
+            parts.2.as_u16()
+        )
+    }
+
+    ///
+    /// Return an [`http::Response<B>`] representation of the error, with a
+    /// YAML body generated from the `into` method.
+    ///
+    /// The string in the response body will be of the format
+    ///
+    /// ```yaml
+    /// message: "<message>"
+    /// domain: "<domain>"
+    /// status: <status>
+    /// ```
+    ///
+    /// The `content-type` header of the response will be `application/yaml`.
+    ///
+    /// # Panics
+    /// Technically the function will panic if, internally, it cannot build
+    /// the response, but since the parts of the response are already verified,
+    /// and the `Body` is only created with `B::from(String)`, that isn't actually
+    /// possible.
+    pub fn as_http_yaml_response<B>(&self) -> Response<B>
+    where
+        B: From<String>,
+    {
+        (self as &dyn HttpError)
+            .response_builder()
+            .header(CONTENT_TYPE, "application/yaml")
+            .body(self.yaml_body().into())
+            .expect("Response::build should succeed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpWhatever;
+    use http::header::CONTENT_TYPE;
+    use http::Response;
+
+    #[test]
+    fn yaml_document() {
+        let response: Response<String> =
+            HttpWhatever::new("503:Cluster:Node \"a\" is: down").as_http_yaml_response();
+
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/yaml"
+        );
+        assert_eq!(
+            response.body(),
+            "message: \"Node \\\"a\\\" is: down\"\ndomain: \"Cluster\"\nstatus: 503\n"
+        );
+    }
+}